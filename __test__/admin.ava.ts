@@ -39,3 +39,23 @@ runner.test("can add admin", async (t, { root, tenk, alice }) => {
   t.deepEqual(await tenk.view("admins"), [alice.accountId]);
 });
 
+
+runner.test("only owner adds and removes admins", async (t, { root, tenk, alice }) => {
+  await root.call(tenk, "add_admin", { account_id: alice });
+  await t.throwsAsync(alice.call(tenk, "add_admin", { account_id: alice }));
+  await t.throwsAsync(alice.call(tenk, "remove_admin", { account_id: alice }));
+  await root.call(tenk, "remove_admin", { account_id: alice });
+  t.deepEqual(await tenk.view("admins"), []);
+  await t.throwsAsync(alice.call(tenk, "update_price", { price: NEAR.parse("1N") }));
+});
+
+runner.test("privileged calls emit events", async (t, { root, tenk }) => {
+  const res = await root.call_raw(tenk, "update_price", { price: NEAR.parse("1N") });
+  const event = res.logs.find((log) => log.startsWith("EVENT_JSON:"));
+  t.truthy(event);
+  const { standard, event: name, data } = JSON.parse(event.slice("EVENT_JSON:".length));
+  t.is(standard, "tenk");
+  t.is(name, "update_price");
+  t.is(data[0].caller, root.accountId);
+  t.is(data[0].args.price, NEAR.parse("1N").toJSON());
+});
@@ -7,7 +7,7 @@ import {
     mint,
     mint_raw,
     now,
-    totalCost,
+    mintDeposit,
 } from "./util";
 
 const price = NEAR.parse("0.8 N");
@@ -30,7 +30,7 @@ const runner = Workspace.init(
 
 runner.test("public allowance should allow only 2 tokens", async (t, { root, tenk, alice }) => {
     await root.call(tenk, "start_sale", {});
-    const cost = await totalCost(tenk, 1, alice.accountId);
+    const cost = await mintDeposit(tenk, 1, alice.accountId);
     await mint(tenk, alice, cost);
     await mint(tenk, alice, cost);
     let last_try = await mint_raw(tenk, alice, cost);
@@ -41,7 +41,7 @@ runner.test("public allowance should allow only 2 tokens", async (t, { root, ten
 
 runner.test("owner has unlimited public allowance", async (t, { root, tenk, alice }) => {
     await root.call(tenk, "start_sale", {});
-    const cost = await totalCost(tenk, 1, alice.accountId);
+    const cost = await mintDeposit(tenk, 1, alice.accountId);
     await mint(tenk, root, cost);
     await mint(tenk, root, cost);
     let last_try = await mint_raw(tenk, root, cost);
@@ -53,7 +53,7 @@ runner.test("owner has unlimited public allowance", async (t, { root, tenk, alic
 
 runner.test("presale allowance should only allow 2", async (t, { root, tenk, alice }) => {
     await root.call(tenk, "add_whitelist_accounts", { accounts: [alice], max_allowance: 2 });
-    const cost = await totalCost(tenk, 1, alice.accountId);
+    const cost = await mintDeposit(tenk, 1, alice.accountId);
     await mint(tenk, alice, cost);
     await mint(tenk, alice, cost);
     let last_try = await mint_raw(tenk, alice, cost);
@@ -65,7 +65,7 @@ runner.test("presale allowance should only allow 2", async (t, { root, tenk, ali
 runner.test("presale allowance should only allow 2 then 2 in public", async (t, { root, tenk, alice }) => {
     await root.call(tenk, "update_allowance", { allowance: 4 });
     await root.call(tenk, "add_whitelist_accounts", { accounts: [alice], max_allowance: 2 });
-    let cost = await totalCost(tenk, 1, alice.accountId);
+    let cost = await mintDeposit(tenk, 1, alice.accountId);
     await mint(tenk, alice, cost);
     await mint(tenk, alice, cost);
     let last_try = await mint_raw(tenk, alice, cost);
@@ -81,7 +81,7 @@ runner.test("presale allowance should only allow 2 then 2 in public", async (t,
     info =  await tenk.view("get_user_sale_info", {account_id: alice});
     t.is(info.remaining_allowance, 2)
 
-    cost = await totalCost(tenk, 1, alice.accountId);
+    cost = await mintDeposit(tenk, 1, alice.accountId);
     await mint(tenk, alice, cost);
     await mint(tenk, alice, cost);
     last_try = await mint_raw(tenk, alice, cost);
@@ -93,7 +93,7 @@ runner.test("presale allowance should only allow 2 then 2 in public", async (t,
 
 runner.test("presale allowance should only allow 1", async (t, { root, tenk, alice }) => {
   await root.call(tenk, "add_whitelist_accounts", { accounts: [alice], max_allowance: 1 });
-  const cost = await totalCost(tenk, 1, alice.accountId);
+  const cost = await mintDeposit(tenk, 1, alice.accountId);
   await mint(tenk, alice, cost);
   let last_try = await mint_raw(tenk, alice, cost);
   t.assert(last_try.failed, "tx didn't fail");
@@ -108,4 +108,42 @@ runner.test("presale allowance should only allow 1", async (t, { root, tenk, ali
   t.assert(await root.call(tenk, "update_allowance", {}));
 
   t.is(await tenk.view("remaining_allowance",{account_id: alice}), null);
-});
\ No newline at end of file
+});
+
+runner.test("public minters are not added to the whitelist", async (t, { root, tenk, alice }) => {
+  await root.call(tenk, "start_sale", {});
+  await mint(tenk, alice, await mintDeposit(tenk, 1, alice.accountId));
+  t.false(await tenk.view("whitelisted", { account_id: alice }));
+  const info = await tenk.view<any>("get_user_sale_info", { account_id: alice });
+  t.false(info.is_vip);
+  t.is(info.remaining_allowance, 1);
+  t.is(await tenk.view("minted_by_account", { account_id: alice }), 1);
+});
+
+runner.test("max_per_account caps mints across phases", async (t, { root, tenk, alice }) => {
+  await root.call(tenk, "update_allowance", { allowance: 4 });
+  await root.call(tenk, "update_max_per_account", { max_per_account: 3 });
+  await root.call(tenk, "add_whitelist_accounts", { accounts: [alice], max_allowance: 2 });
+  const cost = await mintDeposit(tenk, 1, alice.accountId);
+  await mint(tenk, alice, cost);
+  await mint(tenk, alice, cost);
+
+  await root.call(tenk, "start_sale", {});
+  t.is(await tenk.view("remaining_allowance", { account_id: alice }), 1);
+  await mint(tenk, alice, cost);
+  const last_try = await mint_raw(tenk, alice, cost);
+  t.assert(last_try.failed, "tx didn't fail");
+  const tokens = await getTokens(tenk, alice);
+  t.is(tokens.length, 3);
+});
+
+runner.test("larger whitelist allowance applies in the public sale", async (t, { root, tenk, alice }) => {
+  await root.call(tenk, "add_whitelist_accounts", { accounts: [alice], max_allowance: 5 });
+  const cost = await mintDeposit(tenk, 1, alice.accountId);
+  await mint(tenk, alice, cost);
+
+  await root.call(tenk, "start_sale", {});
+  t.is(await tenk.view("remaining_allowance", { account_id: alice }), 4);
+  await root.call(tenk, "update_max_per_account", { max_per_account: 3 });
+  t.is(await tenk.view("remaining_allowance", { account_id: alice }), 2);
+});
@@ -0,0 +1,35 @@
+import { Workspace } from "near-workspaces-ava";
+import { Gas, NEAR } from "near-units";
+import { binPath, deploy, getTokens, mintDeposit } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const tenk = await deploy(root);
+    const minter = await root.createAndDeploy("minter", binPath("mock_minter"));
+    return { tenk, alice, minter };
+  }
+);
+
+const gas = Gas.parse("300 Tgas");
+
+runner.test("contract caller mints to itself", async (t, { tenk, alice, minter }) => {
+  const attachedDeposit = await mintDeposit(tenk, 1, minter.accountId);
+  await alice.call(minter, "mint", { tenk, num: 1 }, { attachedDeposit, gas });
+  t.is((await getTokens(tenk, minter)).length, 1);
+  t.is((await getTokens(tenk, alice)).length, 0);
+});
+
+runner.test("only trusted minters mint on behalf of users", async (t, { root, tenk, alice, minter }) => {
+  const attachedDeposit = await mintDeposit(tenk, 1, alice.accountId);
+  const args = { tenk, account_id: alice, num: 1 };
+  await alice.call_raw(minter, "mint_on_behalf", args, { attachedDeposit, gas });
+  t.is((await getTokens(tenk, alice)).length, 0);
+
+  await root.call(tenk, "add_trusted_minter", { account_id: minter });
+  t.deepEqual(await tenk.view("trusted_minters"), [minter.accountId]);
+  await alice.call(minter, "mint_on_behalf", args, { attachedDeposit, gas });
+  t.is((await getTokens(tenk, alice)).length, 1);
+  t.is(await tenk.view("minted_by_account", { account_id: alice }), 1);
+});
@@ -0,0 +1,41 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, getTokens, mintDeposit, totalCost } from "./util";
+
+const price = NEAR.parse("1 N");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const tenk = await deploy(root, "tenk", {
+      sale: { ...DEFAULT_SALE, price: price.toJSON() },
+    });
+    await root.call(tenk, "update_discounts", { discounts: [{ min: 5, bps: 1_000 }] });
+    await root.call(tenk, "update_bundles", { bundles: [{ num: 3, price: NEAR.parse("2 N").toJSON() }] });
+    return { tenk, alice };
+  }
+);
+
+async function priceOf(tenk, num: number, account): Promise<NEAR> {
+  return totalCost(tenk, num, account.accountId);
+}
+
+runner.test("total_cost includes discounts and bundles", async (t, { tenk, alice }) => {
+  t.assert((await priceOf(tenk, 2, alice)).eq(NEAR.parse("2 N")));
+  t.assert((await priceOf(tenk, 3, alice)).eq(NEAR.parse("2 N")));
+  t.assert((await priceOf(tenk, 5, alice)).eq(NEAR.parse("4.5 N")));
+  await t.throwsAsync(tenk.view("total_cost", { num: 11, minter: alice }));
+});
+
+runner.test("mint at discounted price", async (t, { tenk, alice }) => {
+  const attachedDeposit = await mintDeposit(tenk, 5, alice.accountId);
+  await alice.call(tenk, "nft_mint_many", { num: 5 }, { attachedDeposit });
+  t.is((await getTokens(tenk, alice)).length, 5);
+});
+
+runner.test("discounts must be within mint_rate_limit", async (t, { root, tenk }) => {
+  await t.throwsAsync(
+    root.call(tenk, "update_discounts", { discounts: [{ min: 11, bps: 1_000 }] })
+  );
+});
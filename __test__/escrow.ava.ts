@@ -0,0 +1,73 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import {
+  DEFAULT_SALE,
+  deploy,
+  getDelta,
+  getTokens,
+  mint,
+  now,
+  sleep,
+  mintDeposit,
+} from "./util";
+
+const price = NEAR.parse("1 N");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const tenk = await deploy(root, "tenk", {
+      sale: { ...DEFAULT_SALE, price: price.toJSON() },
+    });
+    return { tenk, alice };
+  }
+);
+
+function setGoal(tenk, root, amount: NEAR, seconds: number) {
+  return root.call(tenk, "update_funding_goal", {
+    funding_goal: { amount: amount.toJSON(), deadline: now() + seconds * 1_000 },
+  });
+}
+
+runner.test("proceeds are released once goal is reached", async (t, { root, tenk, alice }) => {
+  await setGoal(tenk, root, price.muln(2), 60);
+  const token_id = await mint(tenk, alice, await mintDeposit(tenk, 1));
+  t.is(await tenk.view("pending_proceeds", { account_id: root }), "0");
+  let info = await tenk.view<any>("funding_info");
+  t.is(info.status, "Raising");
+  t.is(info.held, price.toString());
+
+  await mint(tenk, alice, await mintDeposit(tenk, 1));
+  info = await tenk.view<any>("funding_info");
+  t.is(info.status, "Reached");
+  t.is(info.held, "0");
+  t.is(await tenk.view("pending_proceeds", { account_id: root }), price.muln(2).toString());
+  t.is(await tenk.view("escrowed_payment", { token_id }), "0");
+  // Replacing a reached goal would make released payments refundable again
+  await t.throwsAsync(setGoal(tenk, root, price.muln(5), -60));
+});
+
+runner.test("refunds if goal is missed", async (t, { root, tenk, alice }) => {
+  const seconds = 10;
+  await setGoal(tenk, root, price.muln(5), seconds);
+  const token_id = await mint(tenk, alice, await mintDeposit(tenk, 1));
+  await t.throwsAsync(alice.call(tenk, "claim_refund", { token_ids: [token_id] }));
+  await sleep(seconds * 1_000);
+
+  t.is((await tenk.view<any>("funding_info")).status, "Failed");
+  await t.throwsAsync(mint(tenk, alice, await mintDeposit(tenk, 1)));
+  await t.throwsAsync(root.call(tenk, "claim_refund", { token_ids: [token_id] }));
+
+  const tokens_left = await tenk.view<number>("tokens_left");
+  const [delta] = await getDelta(t, alice, () =>
+    alice.call(tenk, "claim_refund", { token_ids: [token_id] })
+  );
+  await delta.isGreaterOrEqual(NEAR.parse("0.99 N"));
+  t.is((await getTokens(tenk, alice)).length, 0);
+  // The refunded token can be minted again
+  t.is(await tenk.view("tokens_left"), tokens_left + 1);
+  // Refunds can still be claimed for 30 days
+  await t.throwsAsync(root.call(tenk, "update_funding_goal", { funding_goal: null }));
+  await t.throwsAsync(alice.call(tenk, "claim_refund", { token_ids: [token_id] }));
+});
@@ -0,0 +1,52 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, getTokens, mintDeposit } from "./util";
+
+const price = NEAR.parse("1 N");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root, "tenk", {
+      sale: { ...DEFAULT_SALE, price: price.toJSON(), allowance: 1 },
+    });
+    return { tenk, alice, bob };
+  }
+);
+
+runner.test("mint to another account", async (t, { tenk, alice, bob }) => {
+  const attachedDeposit = await mintDeposit(tenk, 1, alice.accountId);
+  await alice.call(tenk, "nft_mint_to", { receiver_id: bob, num: 1 }, { attachedDeposit });
+  t.is((await getTokens(tenk, bob)).length, 1);
+  t.is((await getTokens(tenk, alice)).length, 0);
+  // Payer's allowance was used
+  t.is(await tenk.view("remaining_allowance", { account_id: alice }), 0);
+  t.is(await tenk.view("remaining_allowance", { account_id: bob }), 1);
+  await t.throwsAsync(
+    alice.call(tenk, "nft_mint_to", { receiver_id: bob, num: 1 }, { attachedDeposit })
+  );
+});
+
+runner.test("gift uses receiver's allowance", async (t, { root, tenk, alice, bob }) => {
+  await root.call(tenk, "update_gift_allowance", { gift_allowance: "Receiver" });
+  const attachedDeposit = await mintDeposit(tenk, 1, alice.accountId);
+  // Receiver must approve the payer first
+  await t.throwsAsync(
+    alice.call(tenk, "nft_mint_to", { receiver_id: bob, num: 1 }, { attachedDeposit })
+  );
+  await bob.call(
+    tenk,
+    "approve_gifts",
+    { payer_id: alice },
+    { attachedDeposit: NEAR.parse("0.01 N") }
+  );
+  t.true(await tenk.view("gifts_approved", { receiver_id: bob, payer_id: alice }));
+  await alice.call(tenk, "nft_mint_to", { receiver_id: bob, num: 1 }, { attachedDeposit });
+  t.is(await tenk.view("remaining_allowance", { account_id: alice }), 1);
+  t.is(await tenk.view("remaining_allowance", { account_id: bob }), 0);
+  await t.throwsAsync(
+    alice.call(tenk, "nft_mint_to", { receiver_id: bob, num: 1 }, { attachedDeposit })
+  );
+});
@@ -0,0 +1,69 @@
+import { Workspace, NearAccount } from "near-workspaces-ava";
+import { Gas, NEAR } from "near-units";
+import { binPath, deploy } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const council = await root.createAccount("council");
+    const tenk = await deploy(root);
+    const dao = await root.createAndDeploy("dao", binPath("mock_dao"), {
+      method: "new",
+      args: { council: [council.accountId] },
+    });
+    return { tenk, alice, council, dao };
+  }
+);
+
+/// Propose calling `method_name` on tenk and approve it as the council
+async function execute(
+  dao: NearAccount,
+  council: NearAccount,
+  tenk: NearAccount,
+  method_name: string,
+  args: Record<string, unknown>
+): Promise<void> {
+  const id = await council.call<number>(dao, "add_proposal", {
+    proposal: {
+      description: method_name,
+      kind: {
+        FunctionCall: {
+          receiver_id: tenk,
+          actions: [
+            {
+              method_name,
+              args: Buffer.from(JSON.stringify(args)).toString("base64"),
+              deposit: "0",
+              gas: Gas.parse("100 Tgas").toString(),
+            },
+          ],
+        },
+      },
+    },
+  });
+  await council.call(dao, "act_proposal", { id, action: "VoteApprove" }, { gas: Gas.parse("200 Tgas") });
+}
+
+runner.test("owner actions go through the DAO", async (t, { root, tenk, alice, council, dao }) => {
+  t.is(await tenk.view("dao"), null);
+  t.assert((await tenk.view<string[]>("governed_methods")).includes("add_admin"));
+  await root.call(tenk, "update_dao", { dao });
+  t.is(await tenk.view("dao"), null);
+  t.is(await tenk.view("pending_dao"), dao.accountId);
+  // Until the DAO accepts, the owner keeps its permissions
+  await t.throwsAsync(alice.call(tenk, "accept_dao", {}));
+  await execute(dao, council, tenk, "accept_dao", {});
+  t.is(await tenk.view("dao"), dao.accountId);
+  t.is(await tenk.view("pending_dao"), null);
+
+  await t.throwsAsync(root.call(tenk, "add_admin", { account_id: alice }));
+  await t.throwsAsync(root.call(tenk, "update_price", { price: NEAR.parse("2 N").toJSON() }));
+
+  await execute(dao, council, tenk, "add_admin", { account_id: alice });
+  t.deepEqual(await tenk.view("admins"), [alice.accountId]);
+
+  await execute(dao, council, tenk, "update_dao", {});
+  t.is(await tenk.view("dao"), null);
+  await root.call(tenk, "remove_admin", { account_id: alice });
+});
@@ -23,6 +23,7 @@ import {
     getDelta,
     create_account_and_claim,
     deploy,
+    deployLinkdropProxy,
     linkdropCost,
 } from "../util";
 
@@ -35,6 +36,7 @@ const runner = Workspace.init(
     { initialBalance: NEAR.parse("15 N").toString() },
     async ({ root }) => {
         const tenk = await deploy(root, "tenk");
+        await deployLinkdropProxy(root, tenk);
         if (Workspace.networkIsSandbox()) {
             const testnet = root.getFullAccount("testnet");
             await testnet.updateAccount({
@@ -11,6 +11,7 @@ import {
   getDelta,
   create_account_and_claim,
   deploy,
+  deployLinkdropProxy,
   linkdropCost,
   sleep,
 } from "../util";
@@ -23,6 +24,7 @@ if (Workspace.networkIsTestnet()) {
     { initialBalance: NEAR.parse("15 N").toString() },
     async ({ root }) => {
       const tenk = await deploy(root, "tenk");
+      await deployLinkdropProxy(root, tenk);
       if (Workspace.networkIsSandbox()) {
         const testnet = root.getFullAccount("testnet");
         await testnet.updateAccount({
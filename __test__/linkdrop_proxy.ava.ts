@@ -0,0 +1,77 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import {
+  checkKey,
+  claim,
+  createLinkdrop,
+  deploy,
+  deployLinkdropProxy,
+  getTokens,
+} from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const tenk = await deploy(root);
+    const proxy = await deployLinkdropProxy(root, tenk);
+    return { tenk, alice, proxy };
+  }
+);
+
+runner.test("claim linkdrop through the proxy", async (t, { root, tenk, alice, proxy }) => {
+  const tokensLeft = await tenk.view<number>("tokens_left");
+  const key = await createLinkdrop(t, tenk, root);
+  const public_key = key.getPublicKey().toString();
+  t.is(await tenk.view("tokens_left"), tokensLeft - 1);
+  t.is(await proxy.view("nft_contract_of", { public_key }), tenk.accountId);
+
+  // Only the proxy can fulfil a linkdrop
+  await t.throwsAsync(
+    alice.call(tenk, "on_linkdrop_claim", { public_key, account_id: alice })
+  );
+
+  const res = await claim(t, tenk, alice, key);
+  t.assert(res.succeeded);
+  t.is((await getTokens(tenk, alice)).length, 1);
+  t.false(await checkKey(key.getPublicKey(), tenk));
+  t.false(await tenk.view("check_key", { public_key }));
+});
+
+runner.test("delete linkdrop from the proxy", async (t, { root, tenk, proxy }) => {
+  const tokensLeft = await tenk.view<number>("tokens_left");
+  const key = await createLinkdrop(t, tenk, root);
+  const public_key = key.getPublicKey().toString();
+  // Linkdrops held by the proxy would be orphaned by changing it
+  await t.throwsAsync(root.call(tenk, "update_linkdrop_proxy", { linkdrop_proxy: null }));
+  await root.call(tenk, "delete_linkdrop", { public_key });
+  t.is(await tenk.view("tokens_left"), tokensLeft);
+  t.is(await proxy.view("nft_contract_of", { public_key }), null);
+  t.false(await checkKey(key.getPublicKey(), tenk));
+  t.true(await root.call(tenk, "update_linkdrop_proxy", { linkdrop_proxy: null }));
+});
+
+runner.test("failed claim keeps the deposit and the key", async (t, { root, tenk, alice, proxy }) => {
+  const key = await createLinkdrop(t, tenk, root);
+  await root.call(tenk, "pause", { scopes: ["Linkdrop"] });
+  const balance = await proxy.availableBalance();
+  await claim(t, tenk, alice, key);
+  t.is((await getTokens(tenk, alice)).length, 0);
+  t.assert(await checkKey(key.getPublicKey(), tenk));
+  // Only gas was spent, the linkdrop deposit never left the proxy
+  t.assert(balance.sub(await proxy.availableBalance()).lt(NEAR.parse("0.02 N")));
+
+  await root.call(tenk, "unpause", { scopes: ["Linkdrop"] });
+  const res = await claim(t, tenk, alice, key);
+  t.assert(res.succeeded);
+  t.is((await getTokens(tenk, alice)).length, 1);
+});
+
+runner.test("claim credits the price paid for the linkdrop", async (t, { root, tenk, alice }) => {
+  await root.call(tenk, "grant_role", { account_id: alice, role: "LinkdropIssuer" });
+  const key = await createLinkdrop(t, tenk, alice);
+  await root.call(tenk, "update_price", { price: NEAR.parse("5 N").toJSON() });
+  await claim(t, tenk, alice, key);
+  const stats = await tenk.view<any>("sale_stats");
+  t.is(stats.total_proceeds, NEAR.parse("1 N").toJSON());
+});
@@ -0,0 +1,47 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { deploy, mint, mintDeposit } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const game = await root.createAccount("game");
+    const tenk = await deploy(root);
+    return { tenk, alice, game };
+  }
+);
+
+const update = { extra: JSON.stringify({ level: 2 }) };
+
+runner.test("token owner can update metadata", async (t, { tenk, alice }) => {
+  const token_id = await mint(tenk, alice, await mintDeposit(tenk, 1));
+  const res = await alice.call_raw(
+    tenk,
+    "nft_update_metadata",
+    { token_id, update },
+    { attachedDeposit: NEAR.parse("10 mN") }
+  );
+  t.assert(res.succeeded);
+  t.assert(
+    res.logs.some(
+      (log) => log.includes('"standard":"tenk"') && log.includes('"event":"nft_metadata_update"')
+    )
+  );
+  const token = await tenk.view<any>("nft_token", { token_id });
+  t.is(token.metadata.extra, update.extra);
+  t.truthy(token.metadata.updated_at);
+});
+
+runner.test("only owner or metadata updater", async (t, { root, tenk, alice, game }) => {
+  const token_id = await mint(tenk, alice, await mintDeposit(tenk, 1));
+  const attachedDeposit = NEAR.parse("10 mN");
+  await t.throwsAsync(
+    game.call(tenk, "nft_update_metadata", { token_id, update }, { attachedDeposit })
+  );
+  await root.call(tenk, "add_metadata_updater", { account_id: game });
+  t.deepEqual(await tenk.view("metadata_updaters"), [game.accountId]);
+  await game.call(tenk, "nft_update_metadata", { token_id, update }, { attachedDeposit });
+  const token = await tenk.view<any>("nft_token", { token_id });
+  t.is(token.metadata.extra, update.extra);
+});
@@ -1,69 +1,38 @@
-import { Workspace, NearAccount, ONE_NEAR } from "near-workspaces-ava";
+import { Workspace, ONE_NEAR } from "near-workspaces-ava";
 import { NEAR, Gas } from "near-units";
-import { nftTokensForOwner } from "./util";
-
-const base_cost = NEAR.parse("0 N");
-const min_cost = NEAR.parse("0 N");
-
-async function deployEmpty(account: NearAccount): Promise<void> {
-  const empty = account.getFullAccount("empty.tn");
-  const bytes = await empty.viewCode();
-  await account.createTransaction(account).deployContract(bytes).signAndSend();
-}
+import {
+  DEFAULT_SALE,
+  deploy,
+  mint,
+  nftTokensForOwner,
+  mintDeposit,
+} from "./util";
 
 function getRoyalties({ root, alice, bob, eve }) {
   return {
     accounts: {
-      [root.accountId]: 10,
-      [alice.accountId]: 10,
-      [bob.accountId]: 10,
-      [eve.accountId]: 70,
+      [root.accountId]: 1_000,
+      [alice.accountId]: 1_000,
+      [bob.accountId]: 1_000,
+      [eve.accountId]: 7_000,
     },
-    percent: 20,
+    percent: 2_000,
   };
 }
 
-const network = "sandbox";
-
 const PRICE = NEAR.parse("3 N");
 
 const runner = Workspace.init(
   { initialBalance: NEAR.parse("15 N").toString() },
   async ({ root }) => {
-    const owner_id = root;
     const alice = await root.createAccount("alice");
     const bob = await root.createAccount("bob");
     const eve = await root.createAccount("eve");
     const royalties = getRoyalties({ root, bob, alice, eve });
-    const tenk = await root.createAndDeploy(
-      "tenk",
-      `${__dirname}/../target/wasm32-unknown-unknown/release/tenk.wasm`,
-      {
-        method: "new_default_meta",
-        args: {
-          owner_id,
-          name: "meerkats",
-          symbol: "N/A",
-          uri: "QmaDR7ozkawfnmEirvErfcJm27FEyFv5U1KQDfWkHGj5qD",
-          size: 10_000,
-          base_cost,
-          min_cost,
-          royalties,
-        },
-        gas: Gas.parse("20 TGas"),
-      }
-    );
-
-    const token_id = (
-      (await root.call(
-        tenk,
-        "nft_mint_one",
-        {},
-        {
-          attachedDeposit: ONE_NEAR,
-        }
-      )) as any
-    ).token_id;
+    const tenk = await deploy(root, "tenk", {
+      sale: { ...DEFAULT_SALE, royalties },
+    });
+    const token_id = await mint(tenk, root, await mintDeposit(tenk, 1));
 
     const mintbase = await root.createAndDeploy(
       "mintbase-market",
@@ -76,14 +45,6 @@ const runner = Workspace.init(
       }
     );
 
-    // await root.call(
-    //   mintbase,
-    //   "storage_deposit",
-    //   {},
-    //   {
-    //     attachedDeposit: ONE_NEAR,
-    //   }
-    // );
     const msg = JSON.stringify({
       price: PRICE,
       autotransfer: true,
@@ -109,13 +70,6 @@ runner.test("buy one", async (t, { root, tenk, mintbase, eve }) => {
   const ids = await nftTokensForOwner(root, tenk);
   t.is(ids.length, 1);
   const token_id = ids[0].token_id;
-  t.log(token_id);
-  // t.log(
-  //   await mintbase.view("get_market_data", {
-  //     nft_contract_id: tenk.accountId,
-  //     token_id,
-  //   })
-  // );
 
   const balance = await root.availableBalance();
   const eveBalance = await eve.availableBalance();
@@ -133,15 +87,12 @@ runner.test("buy one", async (t, { root, tenk, mintbase, eve }) => {
     },
     {
       gas: Gas.parse("100 Tgas"),
-      attachedDeposit: ONE_NEAR,
+      attachedDeposit: PRICE,
     }
   );
-  t.log(await nftTokensForOwner(bob, tenk));
+  t.is((await nftTokensForOwner(bob, tenk)).length, 1);
   const newBalance = await root.availableBalance();
   t.assert(newBalance.gt(balance));
-  t.log(newBalance.sub(balance).toHuman());
   const newEveBalance = await eve.availableBalance();
   t.assert(newEveBalance.gt(eveBalance));
-  t.log(newEveBalance.sub(eveBalance).toHuman());
-  // t.assert(balance.lt(newBalance));
 });
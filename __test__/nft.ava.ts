@@ -3,7 +3,7 @@ import { NEAR } from "near-units";
 import {
   costPerToken,
   tokenStorageCost,
-  totalCost,
+  mintDeposit,
   MINT_ONE_GAS,
   nftTokensForOwner,
   deployEmpty,
@@ -43,7 +43,7 @@ async function assertXTokens(t, root: NearAccount, tenk, num) {
   const method = num == 1 ? "nft_mint_one" : "nft_mint_many";
   let args = num == 1 ? {} : { num };
   const res = await root.call_raw(tenk, method, args, {
-    attachedDeposit: await totalCost(tenk, num),
+    attachedDeposit: await mintDeposit(tenk, num),
     gas: MINT_ONE_GAS,
   });
   t.true(res.succeeded, [res.Failure, ...res.promiseErrorMessages].join("\n"));
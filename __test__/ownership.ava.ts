@@ -0,0 +1,39 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { deploy } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root, "tenk", { backup_owner: bob.accountId });
+    return { tenk, alice, bob };
+  }
+);
+
+runner.test("ownership is transferred once accepted", async (t, { root, tenk, alice, bob }) => {
+  await root.call(tenk, "transfer_ownership", { new_owner: alice });
+  t.is(await tenk.view("pending_owner"), alice.accountId);
+  t.is(await tenk.view("backup_owner"), bob.accountId);
+  await t.throwsAsync(bob.call(tenk, "accept_ownership", {}));
+
+  await alice.call(tenk, "accept_ownership", {});
+  t.is(await tenk.view("pending_owner"), null);
+  await t.throwsAsync(root.call(tenk, "update_price", { price: NEAR.parse("2 N").toJSON() }));
+  await alice.call(tenk, "update_price", { price: NEAR.parse("2 N").toJSON() });
+});
+
+runner.test("proposal can be cancelled", async (t, { root, tenk, alice }) => {
+  await root.call(tenk, "transfer_ownership", { new_owner: alice });
+  await root.call(tenk, "cancel_ownership_transfer", {});
+  await t.throwsAsync(alice.call(tenk, "accept_ownership", {}));
+});
+
+runner.test("backup owner can be removed", async (t, { root, tenk, bob }) => {
+  await bob.call(tenk, "update_price", { price: NEAR.parse("2 N").toJSON() });
+  await t.throwsAsync(bob.call(tenk, "update_backup_owner", {}));
+  await root.call(tenk, "update_backup_owner", {});
+  t.is(await tenk.view("backup_owner"), null);
+  await t.throwsAsync(bob.call(tenk, "update_price", { price: NEAR.parse("3 N").toJSON() }));
+});
@@ -5,7 +5,7 @@ import {
   mint,
   BalanceDelta,
   deploy,
-  totalCost,
+  mintDeposit,
   now,
   DEFAULT_SALE,
 } from "./util";
@@ -53,7 +53,7 @@ const runner = Workspace.init(
     const tenk = await deploy(root, "tenk", {
       sale: { ...DEFAULT_SALE, royalties },
     });
-    const token_id = await mint(tenk, bob, await totalCost(tenk, 1));
+    const token_id = await mint(tenk, bob, await mintDeposit(tenk, 1));
 
     const paras = await delpoyParas(root, root, root, [tenk]);
 
@@ -134,3 +134,25 @@ runner.test("buy one", async (t, { root, tenk, paras, bob, eve }) => {
   t.log(newEveBalance.sub(eveBalance).toHuman());
   // t.assert(balance.lt(newBalance));
 });
+
+runner.test("payout follows NEP-199", async (t, { root, tenk, bob }) => {
+  const [{ token_id }] = await nftTokensForOwner(bob, tenk);
+  const balance = ONE_NEAR;
+  const payout = await tenk.view<any>("nft_payout", {
+    token_id,
+    balance,
+    max_len_payout: 10,
+  });
+  t.deepEqual(Object.keys(payout), ["payout"]);
+  const total = Object.values<string>(payout.payout).reduce(
+    (sum, amount) => sum.add(NEAR.from(amount)),
+    NEAR.from(0)
+  );
+  t.assert(total.lte(balance));
+  t.assert(total.gte(balance.sub(NEAR.from(Object.keys(payout.payout).length))));
+
+  // Three royalty accounts, the owner and the platform's account
+  await t.throwsAsync(
+    tenk.view("nft_payout", { token_id, balance, max_len_payout: 4 })
+  );
+});
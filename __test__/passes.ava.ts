@@ -0,0 +1,61 @@
+import { Workspace } from "near-workspaces-ava";
+import { Gas, NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, getTokens, mint, mint_raw, mintDeposit } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root, "tenk", { size: 10 });
+    return { tenk, alice, bob };
+  }
+);
+
+const attachedDeposit = NEAR.parse("20 mN");
+
+runner.test("issue, transfer and redeem passes", async (t, { root, tenk, alice, bob }) => {
+  await root.call(tenk, "issue_passes", { account_id: alice, num: 2 });
+  t.is(await tenk.view("passes_of", { account_id: alice }), 2);
+  t.is(await tenk.view("tokens_left"), 8);
+  await t.throwsAsync(bob.call(tenk, "issue_passes", { account_id: bob, num: 1 }));
+
+  await t.throwsAsync(alice.call(tenk, "transfer_passes", { receiver_id: bob, num: 0 }, { attachedDeposit }));
+  // The receiver's passes must be paid for
+  await t.throwsAsync(alice.call(tenk, "transfer_passes", { receiver_id: bob, num: 1 }));
+  await alice.call(tenk, "transfer_passes", { receiver_id: bob, num: 1 }, { attachedDeposit });
+  t.is(await tenk.view("passes_of", { account_id: alice }), 1);
+  t.is(await tenk.view("passes_of", { account_id: bob }), 1);
+
+  // Passes are free to redeem unless the sale has a pass_price
+  await bob.call(tenk, "redeem_passes", { num: 1 }, { attachedDeposit });
+  t.is((await getTokens(tenk, bob)).length, 1);
+  t.is(await tenk.view("passes_of", { account_id: bob }), 0);
+  t.is(await tenk.view("tokens_left"), 8);
+  await t.throwsAsync(bob.call(tenk, "redeem_passes", { num: 1 }, { attachedDeposit }));
+});
+
+runner.test("passes reserve tokens", async (t, { root, tenk, alice }) => {
+  await t.throwsAsync(root.call(tenk, "issue_passes", { account_id: alice, num: 11 }));
+  await root.call(tenk, "issue_passes", { account_id: alice, num: 10 });
+  t.is(await tenk.view("tokens_left"), 0);
+  t.assert((await mint_raw(tenk, root, await mintDeposit(tenk, 1))).failed);
+  await alice.call(tenk, "redeem_passes", { num: 2 }, { attachedDeposit: NEAR.parse("40 mN") });
+  t.is((await getTokens(tenk, alice)).length, 2);
+});
+
+runner.test("tokens of the pass contract are exchanged for passes", async (t, { root, tenk, alice }) => {
+  const pass = await deploy(root, "pass", { sale: { ...DEFAULT_SALE, price: "0" } });
+  const token_id = await mint(pass, alice, await mintDeposit(pass, 1));
+  const args = { receiver_id: tenk, token_id, msg: "" };
+  const options = { attachedDeposit: "1", gas: Gas.parse("100 Tgas") };
+
+  await alice.call_raw(pass, "nft_transfer_call", args, options);
+  t.is(await tenk.view("passes_of", { account_id: alice }), 0);
+  t.is((await getTokens(pass, alice)).length, 1);
+
+  await root.call(tenk, "update_pass_contract", { pass_contract: pass });
+  await alice.call(pass, "nft_transfer_call", args, options);
+  t.is(await tenk.view("passes_of", { account_id: alice }), 1);
+  t.is((await getTokens(pass, alice)).length, 0);
+});
@@ -0,0 +1,50 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { deploy, getTokens, mint, mint_raw, mintDeposit } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root);
+    return { tenk, alice, bob };
+  }
+);
+
+runner.test("pausing minting", async (t, { root, tenk, alice }) => {
+  const res = await root.call_raw(tenk, "pause", { scopes: ["Mint"] });
+  t.assert(res.logs.some((log) => log.includes('"event":"pause"')));
+  t.deepEqual(await tenk.view("paused"), ["Mint"]);
+  const cost = await mintDeposit(tenk, 1, alice.accountId);
+  t.assert((await mint_raw(tenk, alice, cost)).failed);
+
+  await root.call(tenk, "unpause", { scopes: ["Mint"] });
+  t.deepEqual(await tenk.view("paused"), []);
+  await mint(tenk, alice, cost);
+  t.is((await getTokens(tenk, alice)).length, 1);
+});
+
+runner.test("pausing transfers", async (t, { root, tenk, alice, bob }) => {
+  const token_id = await mint(tenk, alice, await mintDeposit(tenk, 1, alice.accountId));
+  await root.call(tenk, "pause", {});
+  t.deepEqual(await tenk.view("paused"), ["Mint", "Transfer", "Linkdrop", "Approval"]);
+  await t.throwsAsync(
+    alice.call(tenk, "nft_transfer", { receiver_id: bob, token_id }, { attachedDeposit: "1" })
+  );
+  await t.throwsAsync(
+    alice.call(tenk, "nft_approve", { token_id, account_id: bob }, { attachedDeposit: NEAR.parse("0.01 N") })
+  );
+
+  await root.call(tenk, "unpause", { scopes: ["Transfer"] });
+  await alice.call(tenk, "nft_transfer", { receiver_id: bob, token_id }, { attachedDeposit: "1" });
+  t.is((await getTokens(tenk, bob)).length, 1);
+});
+
+runner.test("pauser role", async (t, { root, tenk, alice }) => {
+  await t.throwsAsync(alice.call(tenk, "pause", {}));
+  await root.call(tenk, "grant_role", { account_id: alice, role: "Pauser" });
+  await alice.call(tenk, "pause", { scopes: ["Linkdrop"] });
+  t.deepEqual(await tenk.view("paused"), ["Linkdrop"]);
+  await t.throwsAsync(alice.call(tenk, "update_price", { price: NEAR.parse("2 N").toJSON() }));
+});
@@ -0,0 +1,63 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, mint, mintDeposit } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const dao = await root.createAccount("dao");
+    const tenk = await deploy(root, "tenk", {
+      sale: {
+        ...DEFAULT_SALE,
+        royalties: { accounts: { [alice.accountId]: 10_000 }, percent: 1_000 },
+      },
+      platform_fee: { account_id: dao.accountId, bps: 250 },
+    });
+    return { tenk, alice, dao };
+  }
+);
+
+runner.test("platform fee is set at init", async (t, { tenk, dao }) => {
+  const platform_fee = { account_id: dao.accountId, bps: 250 };
+  t.deepEqual(await tenk.view("platform_fee"), platform_fee);
+  const sale_info = await tenk.view<any>("get_sale_info");
+  t.deepEqual(sale_info.platform_fee, platform_fee);
+});
+
+runner.test("platform fee is taken from royalties", async (t, { root, tenk, alice, dao }) => {
+  const token_id = await mint(tenk, root, await mintDeposit(tenk, 1));
+  const { payout } = await tenk.view<any>("nft_payout", {
+    token_id,
+    balance: NEAR.parse("100 N"),
+    max_len_payout: 10,
+  });
+  t.is(payout[dao.accountId], NEAR.parse("250 mN").toString());
+  t.is(payout[alice.accountId], NEAR.parse("9.75 N").toString());
+});
+
+runner.test("only fee account can update fee", async (t, { root, tenk, dao }) => {
+  const platform_fee = { account_id: dao.accountId, bps: 100 };
+  await t.throwsAsync(root.call(tenk, "update_platform_fee", { platform_fee }));
+  await dao.call(tenk, "update_platform_fee", { platform_fee });
+  t.deepEqual(await tenk.view("platform_fee"), platform_fee);
+  await t.throwsAsync(
+    dao.call(tenk, "update_platform_fee", {
+      platform_fee: { account_id: dao.accountId, bps: 10_001 },
+    })
+  );
+});
+
+runner.test("zero platform fee adds no payout entry", async (t, { root, tenk, alice, dao }) => {
+  await dao.call(tenk, "update_platform_fee", {
+    platform_fee: { account_id: dao.accountId, bps: 0 },
+  });
+  const token_id = await mint(tenk, root, await mintDeposit(tenk, 1));
+  const { payout } = await tenk.view<any>("nft_payout", {
+    token_id,
+    balance: NEAR.parse("100 N"),
+    max_len_payout: 10,
+  });
+  t.false(dao.accountId in payout);
+  t.is(payout[alice.accountId], NEAR.parse("10 N").toString());
+});
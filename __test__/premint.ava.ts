@@ -4,13 +4,14 @@ import {
   claim,
   createLinkdrop,
   deploy,
+  deployLinkdropProxy,
   getTokens,
   mint,
   mint_raw,
   now,
   sleep,
   start_presale_args,
-  totalCost,
+  mintDeposit,
 } from "./util";
 
 const presale_price = NEAR.parse("0.8 N").toJSON();
@@ -27,6 +28,7 @@ const runner = Workspace.init(
         presale_price,
       },
     });
+    await deployLinkdropProxy(root, tenk);
     return { tenk, alice };
   }
 );
@@ -45,7 +47,7 @@ async function premint_period<T>(
 }
 
 runner.test("premint", async (t, { root, tenk, alice }) => {
-  const cost = await totalCost(tenk, 1, alice.accountId);
+  const cost = await mintDeposit(tenk, 1, alice.accountId);
   const token = await mint(tenk, root);
   const duration = 20;
   const linkkeys = await createLinkdrop(t, tenk, root);
@@ -77,7 +79,7 @@ runner.test("premint", async (t, { root, tenk, alice }) => {
     t.assert(tokens.length == 3);
   });
   t.log(await tenk.view("get_sale_info"));
-  const sale_price = await totalCost(tenk, 1, alice.accountId);
+  const sale_price = await mintDeposit(tenk, 1, alice.accountId);
   t.log(sale_price.toHuman(), cost.toHuman());
   t.assert(sale_price.gt(cost), "actual sale price has increased");
 
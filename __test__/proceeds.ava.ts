@@ -0,0 +1,43 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, getDelta, mint, mintDeposit } from "./util";
+
+const price = NEAR.parse("1 N");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root, "tenk", {
+      sale: { ...DEFAULT_SALE, price: price.toJSON() },
+    });
+    return { tenk, alice, bob };
+  }
+);
+
+async function pendingProceeds(tenk, account): Promise<NEAR> {
+  return NEAR.from(await tenk.view("pending_proceeds", { account_id: account }));
+}
+
+runner.test("proceeds are credited and withdrawn", async (t, { root, tenk, alice }) => {
+  await mint(tenk, alice, await mintDeposit(tenk, 1));
+  t.assert((await pendingProceeds(tenk, root)).eq(price));
+
+  const [rootDelta] = await getDelta(t, root, () => root.call(tenk, "withdraw", {}));
+  await rootDelta.isGreaterOrEqual(NEAR.parse("0.98 N"));
+  t.assert((await pendingProceeds(tenk, root)).isZero());
+  await t.throwsAsync(root.call(tenk, "withdraw", {}));
+});
+
+runner.test("initial royalties are split in the ledger", async (t, { root, tenk, alice, bob }) => {
+  await root.call(tenk, "update_initial_royalties", {
+    initial_royalties: { accounts: { [bob.accountId]: 10_000 }, percent: 5_000 },
+  });
+  await mint(tenk, alice, await mintDeposit(tenk, 1));
+  const bobProceeds = await pendingProceeds(tenk, bob);
+  t.assert(bobProceeds.gt(NEAR.parse("0.4 N")));
+  t.assert(bobProceeds.lt(NEAR.parse("0.5 N")));
+  const [bobDelta] = await getDelta(t, bob, () => bob.call(tenk, "withdraw", {}));
+  await bobDelta.isGreaterOrEqual(NEAR.parse("0.4 N"));
+});
@@ -0,0 +1,49 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, mintDeposit } from "./util";
+
+const price = NEAR.parse("1 N");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root, "tenk", {
+      sale: { ...DEFAULT_SALE, price: price.toJSON() },
+    });
+    await root.call(tenk, "update_referral_program", {
+      referral_program: { bps: 1_000, max_commission: NEAR.parse("0.15 N").toJSON() },
+    });
+    return { tenk, alice, bob };
+  }
+);
+
+async function mintReferred(tenk, minter, referrer, num = 1) {
+  const attachedDeposit = await mintDeposit(tenk, num, minter.accountId);
+  return minter.call(tenk, "nft_mint_many", { num, referrer }, { attachedDeposit });
+}
+
+runner.test("referrer is credited a commission", async (t, { root, tenk, alice, bob }) => {
+  await mintReferred(tenk, alice, bob);
+  const stats = await tenk.view<any>("referral_stats", { account_id: bob });
+  t.is(stats.tokens, 1);
+  t.assert(NEAR.from(stats.earned).eq(NEAR.parse("0.1 N")));
+  t.assert(NEAR.from(await tenk.view("pending_proceeds", { account_id: bob })).eq(NEAR.parse("0.1 N")));
+  t.assert(NEAR.from(await tenk.view("pending_proceeds", { account_id: root })).eq(NEAR.parse("0.9 N")));
+});
+
+runner.test("commission is capped per referrer", async (t, { tenk, alice, bob }) => {
+  await mintReferred(tenk, alice, bob, 2);
+  const stats = await tenk.view<any>("referral_stats", { account_id: bob });
+  t.is(stats.tokens, 2);
+  t.assert(NEAR.from(stats.earned).eq(NEAR.parse("0.15 N")));
+  await mintReferred(tenk, alice, bob);
+  const after = await tenk.view<any>("referral_stats", { account_id: bob });
+  t.is(after.tokens, 3);
+  t.assert(NEAR.from(after.earned).eq(NEAR.parse("0.15 N")));
+});
+
+runner.test("self-referral is rejected", async (t, { tenk, alice }) => {
+  await t.throwsAsync(mintReferred(tenk, alice, alice));
+});
@@ -0,0 +1,49 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, getDelta, getTokens, mintDeposit } from "./util";
+
+const price = NEAR.parse("1 N");
+// Upper bound of storage paid for minting a token
+const storage = NEAR.parse("20 mN");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root, "tenk", {
+      size: 3,
+      sale: { ...DEFAULT_SALE, price: price.toJSON(), allowance: 2 },
+    });
+    return { tenk, alice, bob };
+  }
+);
+
+function mintMany(tenk, account, num: number, attachedDeposit: NEAR) {
+  return account.call(tenk, "nft_mint_many", { num }, { attachedDeposit });
+}
+
+runner.test("overpayment is refunded", async (t, { tenk, alice }) => {
+  const [delta] = await getDelta(t, alice, () => mintMany(tenk, alice, 1, NEAR.parse("5 N")));
+  await delta.isLessOrEqual(price.neg());
+  await delta.isGreaterOrEqual(price.add(storage).neg());
+});
+
+runner.test("only pays for allowance left", async (t, { tenk, alice }) => {
+  const [delta] = await getDelta(t, alice, async () =>
+    mintMany(tenk, alice, 3, await mintDeposit(tenk, 3, alice.accountId))
+  );
+  t.is((await getTokens(tenk, alice)).length, 2);
+  await delta.isLessOrEqual(price.muln(2).neg());
+  await delta.isGreaterOrEqual(price.add(storage).muln(2).neg());
+});
+
+runner.test("only pays for tokens left", async (t, { tenk, alice, bob }) => {
+  await mintMany(tenk, alice, 2, await mintDeposit(tenk, 2, alice.accountId));
+  const [delta] = await getDelta(t, bob, async () =>
+    mintMany(tenk, bob, 2, await mintDeposit(tenk, 2, bob.accountId))
+  );
+  t.is((await getTokens(tenk, bob)).length, 1);
+  await delta.isLessOrEqual(price.neg());
+  await delta.isGreaterOrEqual(price.add(storage).neg());
+});
@@ -0,0 +1,40 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { deploy } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root);
+    return { tenk, alice, bob };
+  }
+);
+
+runner.test("roles are granted and revoked by the owner", async (t, { root, tenk, alice, bob }) => {
+  t.deepEqual(await tenk.view("roles_of", { account_id: alice }), []);
+  const res = await root.call_raw(tenk, "grant_role", { account_id: alice, role: "SaleManager" });
+  t.assert(res.logs.some((log) => log.includes("role_granted")));
+  t.deepEqual(await tenk.view("roles_of", { account_id: alice }), ["SaleManager"]);
+  await t.throwsAsync(alice.call(tenk, "grant_role", { account_id: bob, role: "SaleManager" }));
+
+  await root.call(tenk, "revoke_role", { account_id: alice, role: "SaleManager" });
+  t.deepEqual(await tenk.view("roles_of", { account_id: alice }), []);
+});
+
+runner.test("methods check the role they need", async (t, { root, tenk, alice }) => {
+  await root.call(tenk, "grant_role", { account_id: alice, role: "SaleManager" });
+  await alice.call(tenk, "update_price", { price: NEAR.parse("2 N").toJSON() });
+  await t.throwsAsync(
+    alice.call(tenk, "update_royalties", { royalties: { accounts: { [alice.accountId]: 10_000 }, percent: 100 } })
+  );
+  await t.throwsAsync(alice.call(tenk, "update_uri", { uri: "https://example.com" }));
+  await t.throwsAsync(alice.call(tenk, "remove_whitelist_account", { account_id: alice }));
+});
+
+runner.test("admins have every role", async (t, { root, tenk, bob }) => {
+  await root.call(tenk, "add_admin", { account_id: bob });
+  t.is((await tenk.view<string[]>("roles_of", { account_id: bob })).length, 6);
+  await bob.call(tenk, "update_uri", { uri: "https://example.com" });
+});
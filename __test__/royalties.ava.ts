@@ -1,6 +1,6 @@
 import { Workspace, NearAccount } from "near-workspaces-ava";
 import { NEAR } from "near-units";
-import { DEFAULT_SALE, deploy, getDelta, mint, now, totalCost } from "./util";
+import { DEFAULT_SALE, deploy, getDelta, mint, now, mintDeposit } from "./util";
 import { Royalties, Sale } from "..";
 
 if (Workspace.networkIsSandbox()) {
@@ -78,7 +78,7 @@ if (Workspace.networkIsSandbox()) {
 
   runner.test("Initial Payout", async (t, { root, tenk, eve }) => {
     let charlie = await root.createAccount("charlie");
-    const cost = await totalCost(tenk, 1);
+    const cost = await mintDeposit(tenk, 1);
     let [delta, token_id] = await getDelta(t, eve, async () =>
       mint(tenk, charlie, cost)
     );
@@ -155,4 +155,39 @@ if (Workspace.networkIsSandbox()) {
       "initial"
     );
   });
+  runner.test("token royalties override collection", async (t, { root, tenk, alice, bob }) => {
+    const token_id = await mint(tenk, root);
+    const royalties = { accounts: { [bob.accountId]: 10_000 }, percent: 1_000 };
+    await root.call(tenk, "set_token_royalties", { token_ids: [token_id], royalties });
+    t.deepEqual(await tenk.view("royalties_of", { token_id }), royalties);
+
+    const payout = await tenk.view<any>("nft_payout", {
+      token_id,
+      balance: NEAR.parse("100 N"),
+      max_len_payout: 10,
+    });
+    t.deepEqual(Object.keys(payout.payout).sort(), [bob.accountId, root.accountId, "tenk.testnet"].sort());
+
+    await root.call(tenk, "set_token_royalties", { token_ids: [token_id] });
+    t.is(Object.keys((await tenk.view<any>("royalties_of", { token_id })).accounts).length, 4);
+  });
+
+  runner.test("royalty ranges", async (t, { root, tenk, alice }) => {
+    const royalties = { accounts: { [alice.accountId]: 10_000 }, percent: 500 };
+    await root.call(tenk, "add_royalty_range", { start: 0, end: 9, royalties });
+    t.deepEqual(await tenk.view("royalties_of", { token_id: "5" }), royalties);
+    t.notDeepEqual(await tenk.view("royalties_of", { token_id: "10" }), royalties);
+    await t.throwsAsync(
+      root.call(tenk, "add_royalty_range", { start: 9, end: 20, royalties })
+    );
+    await t.throwsAsync(
+      root.call(tenk, "add_royalty_range", {
+        start: 20,
+        end: 30,
+        royalties: { accounts: { [alice.accountId]: 9_000 }, percent: 500 },
+      })
+    );
+    await root.call(tenk, "remove_royalty_range", { start: 0 });
+    t.deepEqual(await tenk.view("royalty_ranges"), []);
+  });
 }
@@ -0,0 +1,60 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, getTokens } from "./util";
+
+const price = NEAR.parse("2 N");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const tenk = await deploy(root);
+    await root.call(tenk, "create_series", {
+      series_id: "gold",
+      series: {
+        title: "Gold",
+        uri: "https://gold.example",
+        size: 3,
+        sale: { ...DEFAULT_SALE, price: price.toJSON(), allowance: 2 },
+      },
+    });
+    return { tenk, alice };
+  }
+);
+
+runner.test("mint from a series", async (t, { tenk, alice }) => {
+  const num = 2;
+  const cost = NEAR.from(
+    await tenk.view("series_mint_deposit", { series_id: "gold", num, minter: alice })
+  );
+  // Includes storage
+  t.assert(cost.gt(price.muln(num)));
+  await alice.call(tenk, "nft_mint_series", { series_id: "gold", num }, { attachedDeposit: cost });
+  const tokens = await getTokens(tenk, alice);
+  t.is(tokens.length, num);
+  for (const token of tokens) {
+    t.assert(token.token_id.startsWith("gold:"));
+    t.assert(token.metadata.media.startsWith("https://gold.example/"));
+  }
+  const info = await tenk.view<any>("get_series_info", { series_id: "gold" });
+  t.is(info.tokens_left, 1);
+  t.is(info.sale_info.token_final_supply, 3);
+  t.deepEqual(await tenk.view("series_ids"), ["gold"]);
+});
+
+runner.test("series allowance", async (t, { tenk, alice }) => {
+  const attachedDeposit = NEAR.parse("5 N");
+  await alice.call(tenk, "nft_mint_series", { series_id: "gold", num: 2 }, { attachedDeposit });
+  await t.throwsAsync(
+    alice.call(tenk, "nft_mint_series", { series_id: "gold", num: 1 }, { attachedDeposit })
+  );
+});
+
+runner.test("series ids can't be reused", async (t, { root, tenk }) => {
+  await t.throwsAsync(
+    root.call(tenk, "create_series", {
+      series_id: "gold",
+      series: { title: "Gold", uri: "https://gold.example", size: 3 },
+    })
+  );
+});
@@ -3,7 +3,7 @@
 // import {
 //   costPerToken,
 //   tokenStorageCost,
-//   totalCost,
+//   mintDeposit,
 //   MINT_ONE_GAS,
 //   nftTokensForOwner,
 //   deployEmpty,
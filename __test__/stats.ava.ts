@@ -0,0 +1,40 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { DEFAULT_SALE, deploy, mint, mintDeposit } from "./util";
+
+const price = NEAR.parse("1 N");
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const bob = await root.createAccount("bob");
+    const tenk = await deploy(root, "tenk", {
+      sale: { ...DEFAULT_SALE, price: price.toJSON() },
+    });
+    return { tenk, alice, bob };
+  }
+);
+
+runner.test("sale and account stats", async (t, { root, tenk, alice, bob }) => {
+  await root.call(tenk, "update_initial_royalties", {
+    initial_royalties: { accounts: { [bob.accountId]: 10_000 }, percent: 5_000 },
+  });
+  await mint(tenk, alice, await mintDeposit(tenk, 1));
+  await mint(tenk, alice, await mintDeposit(tenk, 1));
+
+  const stats = await tenk.view<any>("sale_stats");
+  t.is(stats.tokens_minted, 2);
+  t.assert(NEAR.from(stats.total_proceeds).eq(price.muln(2)));
+  t.assert(NEAR.from(stats.public_sale_proceeds).eq(price.muln(2)));
+  t.assert(NEAR.from(stats.presale_proceeds).isZero());
+  t.assert(NEAR.from(stats.royalties_paid).gte(price));
+
+  const aliceStats = await tenk.view<any>("account_stats", { account_id: alice });
+  t.is(aliceStats.minted, 2);
+  t.assert(NEAR.from(aliceStats.spent).eq(price.muln(2)));
+
+  const bobStats = await tenk.view<any>("account_stats", { account_id: bob });
+  t.is(bobStats.minted, 0);
+  t.assert(NEAR.from(bobStats.spent).isZero());
+});
@@ -0,0 +1,38 @@
+import { Workspace } from "near-workspaces-ava";
+import { NEAR } from "near-units";
+import { deploy } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const tenk = await deploy(root);
+    return { tenk, alice };
+  }
+);
+
+const price = NEAR.parse("2 N").toJSON();
+
+runner.test("changes take effect immediately without a timelock", async (t, { root, tenk }) => {
+  await root.call(tenk, "update_price", { price });
+  t.is((await tenk.view<any>("get_sale_info")).price, price);
+  t.deepEqual(await tenk.view("pending_changes"), []);
+});
+
+runner.test("timelocked changes are queued and can be cancelled", async (t, { root, tenk, alice }) => {
+  const day = 24 * 60 * 60 * 1000;
+  await root.call(tenk, "update_timelock", { timelock: day });
+  await root.call(tenk, "update_price", { price });
+  t.not((await tenk.view<any>("get_sale_info")).price, price);
+  const [pending] = await tenk.view<any[]>("pending_changes");
+  t.deepEqual(pending.change, { Price: price });
+  await t.throwsAsync(root.call(tenk, "execute_change", { id: pending.id }));
+  await t.throwsAsync(alice.call(tenk, "cancel_change", { id: pending.id }));
+
+  await root.call(tenk, "cancel_change", { id: pending.id });
+  t.deepEqual(await tenk.view("pending_changes"), []);
+
+  // Removing the timelock must also wait for it
+  await root.call(tenk, "update_timelock", {});
+  t.is(await tenk.view("timelock"), day);
+});
@@ -0,0 +1,43 @@
+import { Workspace } from "near-workspaces-ava";
+import { Gas, NEAR } from "near-units";
+import { readFileSync } from "fs";
+import { binPath, deploy, sleep } from "./util";
+
+const runner = Workspace.init(
+  { initialBalance: NEAR.parse("20 N").toString() },
+  async ({ root }) => {
+    const alice = await root.createAccount("alice");
+    const tenk = await deploy(root);
+    return { tenk, alice };
+  }
+);
+
+const gas = Gas.parse("300 Tgas");
+
+runner.test("owner upgrades the contract", async (t, { root, tenk, alice }) => {
+  const code = readFileSync(binPath("tenk"));
+  const failed = await alice.call_raw(tenk, "upgrade", code, { gas });
+  t.assert(failed.failed);
+
+  const res = await root.call_raw(tenk, "upgrade", code, { gas });
+  t.assert(res.succeeded);
+  t.assert(res.logs.some((log) => log.includes('"event":"upgrade"')));
+  t.assert(res.logs.some((log) => log.includes("Migrated from version 2 to 2")));
+  t.is(await tenk.view("contract_version"), 2);
+  t.is(await tenk.view("owner"), root.accountId);
+});
+
+runner.test("upgrade waits for the timelock", async (t, { root, tenk }) => {
+  const code = readFileSync(binPath("tenk"));
+  await root.call(tenk, "update_timelock", { timelock: 1_000 });
+  const res = await root.call_raw(tenk, "upgrade", code, { gas });
+  t.assert(res.succeeded);
+  t.false(res.logs.some((log) => log.includes("Migrated")));
+  const [change] = await tenk.view<any[]>("pending_changes");
+  t.truthy(change.change.Upgrade);
+
+  await sleep(1_000);
+  const executed = await root.call_raw(tenk, "execute_change", { id: change.id }, { gas });
+  t.assert(executed.succeeded);
+  t.assert(executed.logs.some((log) => log.includes("Migrated from version 2 to 2")));
+});
@@ -114,6 +114,14 @@ export async function totalCost(
   return NEAR.from(await tenk.view("total_cost", { num, minter }));
 }
 
+export async function mintDeposit(
+  tenk: NearAccount,
+  num: number,
+  minter: string = "alice.near"
+): Promise<NEAR> {
+  return NEAR.from(await tenk.view("mint_deposit", { num, minter }));
+}
+
 export async function linkdropCost(
   tenk: NearAccount,
   minter: string = "alice.near"
@@ -149,13 +157,33 @@ function costOfMinting(num: number): string {
   return ONE_NFT_STORAGE_COST_BN.mul(new BN(num)).toString();
 }
 
+/// Deploy a linkdrop proxy and use it for `tenk`'s linkdrops
+export async function deployLinkdropProxy(
+  root: NearAccount,
+  tenk: NearAccount
+): Promise<NearAccount> {
+  const proxy = await root.createAndDeploy("linkdrop", binPath("linkdrop_proxy"), {
+    method: "new",
+    args: {},
+  });
+  await root.call(tenk, "update_linkdrop_proxy", { linkdrop_proxy: proxy });
+  return proxy;
+}
+
+/// Linkdrop proxy holding the keys of `tenk`'s linkdrops
+export async function linkdropProxy(tenk: NearAccount): Promise<NearAccount> {
+  return tenk.getFullAccount(await tenk.view<string>("linkdrop_proxy"));
+}
+
+/// Whether `key` is an access key of the linkdrop proxy used by `tenk`
 export async function checkKey(
   key: PublicKey,
-  contract: NearAccount
+  tenk: NearAccount
 ): Promise<boolean> {
-  const provider = ((contract as any).manager as AccountManager).provider;
+  const proxy = await linkdropProxy(tenk);
+  const provider = ((proxy as any).manager as AccountManager).provider;
   try {
-    const res = await provider.view_access_key(contract.accountId, key);
+    const res = await provider.view_access_key(proxy.accountId, key);
     return res.permission.FunctionCall.method_names[0] === "claim";
     // return true;
   } catch (_) {
@@ -187,7 +215,7 @@ export async function createLinkdrop(
       },
       {
         attachedDeposit,
-        gas: Gas.parse("40 Tgas"),
+        gas: Gas.parse("100 Tgas"),
       }
     );
     return res;
@@ -206,8 +234,9 @@ export async function claim(
   alice: NearAccount,
   signWithKey: KeyPair
 ): Promise<TransactionResult> {
-  return tenk.call_raw(
-    tenk,
+  const proxy = await linkdropProxy(tenk);
+  return proxy.call_raw(
+    proxy,
     "claim",
     {
       account_id: alice,
@@ -219,14 +248,15 @@ export async function claim(
   )
 }
 
-export function claim_raw(
+export async function claim_raw(
   tenk: NearAccount,
   account_id: NearAccount,
   signWithKey: KeyPair,
   gas = Gas.parse("100 Tgas")
-) {
-  return tenk.call_raw(
-    tenk,
+): Promise<TransactionResult> {
+  const proxy = await linkdropProxy(tenk);
+  return proxy.call_raw(
+    proxy,
     "claim",
     {
       account_id,
@@ -260,8 +290,9 @@ export async function create_account_and_claim(
 ): Promise<NearAccount> {
   const actualKey = createKeyPair();
   const new_public_key = actualKey.getPublicKey().toString();
-  const res = await contract.call_raw(
-    contract,
+  const proxy = await linkdropProxy(contract);
+  const res = await proxy.call_raw(
+    proxy,
     "create_account_and_claim",
     {
       new_account_id,
@@ -0,0 +1,362 @@
+//! Linkdrop proxy which any NFT contract can use to give away tokens with a link.
+//!
+//! The NFT contract reserves a token and calls `create_linkdrop` with `LINKDROP_DEPOSIT` attached,
+//! which adds the public key of the link as an access key of this contract. Whoever has the
+//! private key can then call `claim` for an existing account or `create_account_and_claim` for
+//! a new one, after which this contract calls `on_linkdrop_claim(public_key, account_id)` on the
+//! NFT contract to fulfil the reservation. Only once the NFT contract has minted the token is the
+//! deposit sent to the account or used to create it. If the NFT contract returns `null` the claim
+//! can be retried and the key is added back, while if it fails the linkdrop is dropped and the
+//! deposit returned to the NFT contract.
+//! The proxy pays for storing the keys, and the gas of claims up to each key's allowance, from its
+//! own balance.
+
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::LookupMap,
+    env, ext_contract,
+    json_types::U128,
+    log, near_bindgen, require, serde_json, AccountId, Balance, BorshStorageKey, Gas,
+    PanicOnDefault, Promise, PromiseResult, PublicKey,
+};
+use near_units::parse_near;
+
+/// Sent to the claiming account, or used to create it
+pub const LINKDROP_DEPOSIT: Balance = parse_near!("0.02 N");
+/// Allowance of the access keys, which bounds the gas a key can spend on claims
+const ACCESS_KEY_ALLOWANCE: Balance = parse_near!("0.1 N");
+const NO_DEPOSIT: Balance = 0;
+
+/// can take 0.5 of access key since gas required is 6.6 times what was actually used
+const ON_CREATE_ACCOUNT_GAS: Gas = Gas(30_000_000_000_000);
+/// Gas attached to the NFT contract's `on_linkdrop_claim`
+const ON_LINKDROP_CLAIM_GAS: Gas = Gas(40_000_000_000_000);
+/// Gas attached to the callback after claiming, which may create the account
+const ON_CLAIM_GAS: Gas = Gas(40_000_000_000_000);
+
+#[ext_contract(ext_linkdrop)]
+trait ExtLinkdrop {
+    fn create_account(&mut self, new_account_id: AccountId, new_public_key: PublicKey) -> Promise;
+}
+
+/// Interface an NFT contract implements to use the proxy
+#[ext_contract(ext_nft)]
+pub trait LinkdropReceiver {
+    /// Mint the token reserved for `public_key` to `account_id`. Called once the key has been
+    /// deleted and before the account is created or sent the linkdrop deposit. Returns `null` if
+    /// the claim can be retried later, e.g. while claims are paused, and panics if it never can.
+    fn on_linkdrop_claim(&mut self, public_key: PublicKey, account_id: AccountId);
+}
+
+#[ext_contract(ext_self)]
+trait OnClaim {
+    fn on_claim(
+        &mut self,
+        public_key: PublicKey,
+        account_id: AccountId,
+        nft_contract: AccountId,
+        new_public_key: Option<PublicKey>,
+    ) -> bool;
+}
+
+#[derive(BorshSerialize, BorshStorageKey)]
+enum StorageKey {
+    Linkdrops,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Contract {
+    /// NFT contract which created the linkdrop of each key
+    linkdrops: LookupMap<PublicKey, AccountId>,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            linkdrops: LookupMap::new(StorageKey::Linkdrops),
+        }
+    }
+
+    /// Add `public_key` as a key which can claim a token reserved by the calling NFT contract.
+    /// Requires `LINKDROP_DEPOSIT` to be attached.
+    #[payable]
+    pub fn create_linkdrop(&mut self, public_key: PublicKey) -> Promise {
+        require!(
+            env::attached_deposit() >= LINKDROP_DEPOSIT,
+            "Not enough attached deposit for linkdrop"
+        );
+        self.add_key(public_key, env::predecessor_account_id())
+    }
+
+    /// Delete a linkdrop created by the calling NFT contract, returning its deposit
+    pub fn delete_linkdrop(&mut self, public_key: PublicKey) -> Promise {
+        let nft_contract = env::predecessor_account_id();
+        require!(
+            self.linkdrops.get(&public_key).as_ref() == Some(&nft_contract),
+            "Linkdrop was not created by caller"
+        );
+        self.delete_key(public_key)
+            .1
+            .then(Promise::new(nft_contract).transfer(LINKDROP_DEPOSIT))
+    }
+
+    /// Claim the token of the linkdrop whose key this transaction is signed with
+    #[private]
+    pub fn claim(&mut self, account_id: AccountId) -> Promise {
+        let public_key = env::signer_account_pk();
+        let (nft_contract, deletion_promise) = self.delete_key(public_key.clone());
+        deletion_promise.then(Self::fulfil(public_key, account_id, nft_contract, None))
+    }
+
+    /// Create new account and claim the token of the linkdrop whose key this transaction is
+    /// signed with to it.
+    #[private]
+    pub fn create_account_and_claim(
+        &mut self,
+        new_account_id: AccountId,
+        new_public_key: PublicKey,
+    ) -> Promise {
+        let public_key = env::signer_account_pk();
+        let (nft_contract, deletion_promise) = self.delete_key(public_key.clone());
+        deletion_promise.then(Self::fulfil(
+            public_key,
+            new_account_id,
+            nft_contract,
+            Some(new_public_key),
+        ))
+    }
+
+    /// Send the linkdrop deposit to the account, or create it with `new_public_key`, once the NFT
+    /// contract has minted the token. Add the key back if the claim can be retried, otherwise
+    /// return the deposit to the NFT contract.
+    #[private]
+    pub fn on_claim(
+        &mut self,
+        public_key: PublicKey,
+        account_id: AccountId,
+        nft_contract: AccountId,
+        new_public_key: Option<PublicKey>,
+    ) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::Successful(result) if !is_null(&result) => {}
+            PromiseResult::Successful(_) => {
+                log!("Claim can be retried, adding key back");
+                self.add_key(public_key, nft_contract);
+                return false;
+            }
+            _ => {
+                log!("Claim failed, returning deposit to {}", &nft_contract);
+                Promise::new(nft_contract).transfer(LINKDROP_DEPOSIT);
+                return false;
+            }
+        }
+        if let Some(new_public_key) = new_public_key {
+            log!("creating account for {}", &account_id);
+            ext_linkdrop::create_account(
+                account_id,
+                new_public_key,
+                self.get_linkdrop_contract(),
+                LINKDROP_DEPOSIT,
+                ON_CREATE_ACCOUNT_GAS,
+            );
+        } else {
+            Promise::new(account_id).transfer(LINKDROP_DEPOSIT);
+        }
+        true
+    }
+
+    /// Returns the balance associated with given key.
+    pub fn get_key_balance(&self) -> U128 {
+        LINKDROP_DEPOSIT.into()
+    }
+
+    /// Whether there is a linkdrop for a key
+    pub fn check_key(&self, public_key: PublicKey) -> bool {
+        self.linkdrops.contains_key(&public_key)
+    }
+
+    /// NFT contract whose token a key can claim
+    pub fn nft_contract_of(&self, public_key: PublicKey) -> Option<AccountId> {
+        self.linkdrops.get(&public_key)
+    }
+
+    pub fn get_linkdrop_contract(&self) -> AccountId {
+        AccountId::new_unchecked(
+            (if cfg!(feature = "testnet") {
+                "testnet"
+            } else {
+                "near"
+            })
+            .to_string(),
+        )
+    }
+}
+
+/// Whether a JSON result is `null`
+fn is_null(result: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(result).map_or(false, |value| value.is_null())
+}
+
+impl Contract {
+    fn fulfil(
+        public_key: PublicKey,
+        account_id: AccountId,
+        nft_contract: AccountId,
+        new_public_key: Option<PublicKey>,
+    ) -> Promise {
+        ext_nft::on_linkdrop_claim(
+            public_key.clone(),
+            account_id.clone(),
+            nft_contract.clone(),
+            NO_DEPOSIT,
+            ON_LINKDROP_CLAIM_GAS,
+        )
+        .then(ext_self::on_claim(
+            public_key,
+            account_id,
+            nft_contract,
+            new_public_key,
+            env::current_account_id(),
+            NO_DEPOSIT,
+            ON_CLAIM_GAS,
+        ))
+    }
+
+    fn add_key(&mut self, public_key: PublicKey, nft_contract: AccountId) -> Promise {
+        if self.linkdrops.insert(&public_key, &nft_contract).is_some() {
+            env::panic_str("key already added");
+        }
+        Promise::new(env::current_account_id()).add_access_key(
+            public_key,
+            ACCESS_KEY_ALLOWANCE,
+            env::current_account_id(),
+            "claim,create_account_and_claim".to_string(),
+        )
+    }
+
+    fn delete_key(&mut self, public_key: PublicKey) -> (AccountId, Promise) {
+        let nft_contract = self
+            .linkdrops
+            .remove(&public_key)
+            .unwrap_or_else(|| env::panic_str("Can't use a full access key."));
+        (
+            nft_contract,
+            Promise::new(env::current_account_id()).delete_key(public_key),
+        )
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils::VMContextBuilder, testing_env, RuntimeFeesConfig, VMConfig};
+
+    fn proxy() -> AccountId {
+        AccountId::new_unchecked("proxy.near".to_string())
+    }
+
+    fn nft() -> AccountId {
+        AccountId::new_unchecked("nft.near".to_string())
+    }
+
+    fn alice() -> AccountId {
+        AccountId::new_unchecked("alice.near".to_string())
+    }
+
+    fn public_key() -> PublicKey {
+        "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+            .parse()
+            .unwrap()
+    }
+
+    fn called_by(account_id: AccountId, attached_deposit: Balance) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(proxy())
+            .predecessor_account_id(account_id)
+            .signer_account_pk(public_key())
+            .attached_deposit(attached_deposit)
+            .build());
+    }
+
+    /// Proxy holding a linkdrop created by `nft()`
+    fn with_linkdrop() -> Contract {
+        called_by(nft(), LINKDROP_DEPOSIT);
+        let mut contract = Contract::new();
+        contract.create_linkdrop(public_key());
+        contract
+    }
+
+    /// Proxy whose linkdrop has been claimed, with the NFT contract's `on_linkdrop_claim` having
+    /// returned `result`
+    fn claimed_with(result: PromiseResult) -> Contract {
+        let mut contract = with_linkdrop();
+        called_by(proxy(), 0);
+        contract.claim(alice());
+        testing_env!(
+            VMContextBuilder::new()
+                .current_account_id(proxy())
+                .predecessor_account_id(proxy())
+                .build(),
+            VMConfig::test(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![result],
+        );
+        contract
+    }
+
+    #[test]
+    fn create_linkdrop_adds_key() {
+        let contract = with_linkdrop();
+        assert!(contract.check_key(public_key()));
+        assert_eq!(contract.nft_contract_of(public_key()), Some(nft()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough attached deposit for linkdrop")]
+    fn create_linkdrop_requires_deposit() {
+        called_by(nft(), LINKDROP_DEPOSIT - 1);
+        Contract::new().create_linkdrop(public_key());
+    }
+
+    #[test]
+    fn claim_removes_key() {
+        let mut contract = claimed_with(PromiseResult::Successful(b"{}".to_vec()));
+        assert!(!contract.check_key(public_key()));
+        assert!(contract.on_claim(public_key(), alice(), nft(), None));
+        assert!(!contract.check_key(public_key()));
+    }
+
+    #[test]
+    fn retryable_claim_adds_key_back() {
+        let mut contract = claimed_with(PromiseResult::Successful(b"null".to_vec()));
+        assert!(!contract.on_claim(public_key(), alice(), nft(), None));
+        assert_eq!(contract.nft_contract_of(public_key()), Some(nft()));
+    }
+
+    #[test]
+    fn failed_claim_drops_key() {
+        let mut contract = claimed_with(PromiseResult::Failed);
+        assert!(!contract.on_claim(public_key(), alice(), nft(), None));
+        assert!(!contract.check_key(public_key()));
+    }
+
+    #[test]
+    fn delete_linkdrop_removes_key() {
+        let mut contract = with_linkdrop();
+        contract.delete_linkdrop(public_key());
+        assert!(!contract.check_key(public_key()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Linkdrop was not created by caller")]
+    fn only_creator_can_delete_linkdrop() {
+        let mut contract = with_linkdrop();
+        called_by(alice(), 0);
+        contract.delete_linkdrop(public_key());
+    }
+}
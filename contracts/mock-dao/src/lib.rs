@@ -0,0 +1,129 @@
+//! Contract used in tests as a Sputnik-style DAO, which governs tenk through proposals.
+//! Proposals are executed as soon as any council member approves them.
+
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    env,
+    json_types::{Base64VecU8, U128, U64},
+    near_bindgen, require,
+    serde::{Deserialize, Serialize},
+    AccountId, Gas, PanicOnDefault, Promise,
+};
+
+/// Function call made when a proposal is approved
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActionCall {
+    pub method_name: String,
+    pub args: Base64VecU8,
+    pub deposit: U128,
+    pub gas: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalKind {
+    FunctionCall {
+        receiver_id: AccountId,
+        actions: Vec<ActionCall>,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalStatus {
+    InProgress,
+    Approved,
+    Rejected,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalInput {
+    pub description: String,
+    pub kind: ProposalKind,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub proposer: AccountId,
+    pub description: String,
+    pub kind: ProposalKind,
+    pub status: ProposalStatus,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Action {
+    VoteApprove,
+    VoteReject,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Contract {
+    council: Vec<AccountId>,
+    proposals: Vec<Proposal>,
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn new(council: Vec<AccountId>) -> Self {
+        Self {
+            council,
+            proposals: Vec::new(),
+        }
+    }
+
+    /// Add a proposal, returning its id
+    pub fn add_proposal(&mut self, proposal: ProposalInput) -> u64 {
+        self.proposals.push(Proposal {
+            proposer: env::predecessor_account_id(),
+            description: proposal.description,
+            kind: proposal.kind,
+            status: ProposalStatus::InProgress,
+        });
+        self.proposals.len() as u64 - 1
+    }
+
+    pub fn get_proposal(&self, id: u64) -> Option<&Proposal> {
+        self.proposals.get(id as usize)
+    }
+
+    /// Approve or reject a proposal. An approved proposal is executed immediately
+    pub fn act_proposal(&mut self, id: u64, action: Action) {
+        require!(
+            self.council.contains(&env::predecessor_account_id()),
+            "Only council members can act on proposals"
+        );
+        let proposal = self
+            .proposals
+            .get_mut(id as usize)
+            .unwrap_or_else(|| env::panic_str("No such proposal"));
+        require!(
+            proposal.status == ProposalStatus::InProgress,
+            "Proposal is not in progress"
+        );
+        if let Action::VoteReject = action {
+            proposal.status = ProposalStatus::Rejected;
+            return;
+        }
+        proposal.status = ProposalStatus::Approved;
+        let ProposalKind::FunctionCall {
+            receiver_id,
+            actions,
+        } = proposal.kind.clone();
+        actions
+            .into_iter()
+            .fold(Promise::new(receiver_id), |promise, action| {
+                promise.function_call(
+                    action.method_name,
+                    action.args.into(),
+                    action.deposit.0,
+                    Gas(action.gas.0),
+                )
+            });
+    }
+}
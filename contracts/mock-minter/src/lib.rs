@@ -0,0 +1,34 @@
+//! Contract used in tests to call tenk from another contract, e.g. like a DAO or sales contract would.
+
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    env, ext_contract, near_bindgen, AccountId, Gas, Promise,
+};
+
+const GAS_FOR_MINT: Gas = Gas(200_000_000_000_000);
+
+#[ext_contract(ext_tenk)]
+trait Tenk {
+    fn nft_mint_many(&mut self, num: u16);
+
+    fn nft_mint_on_behalf(&mut self, account_id: AccountId, num: u16);
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Contract {}
+
+#[near_bindgen]
+impl Contract {
+    /// Mint from `tenk` with this contract as the minter, forwarding the attached deposit
+    #[payable]
+    pub fn mint(&mut self, tenk: AccountId, num: u16) -> Promise {
+        ext_tenk::nft_mint_many(num, tenk, env::attached_deposit(), GAS_FOR_MINT)
+    }
+
+    /// Mint from `tenk` on behalf of `account_id`, forwarding the attached deposit
+    #[payable]
+    pub fn mint_on_behalf(&mut self, tenk: AccountId, account_id: AccountId, num: u16) -> Promise {
+        ext_tenk::nft_mint_on_behalf(account_id, num, tenk, env::attached_deposit(), GAS_FOR_MINT)
+    }
+}
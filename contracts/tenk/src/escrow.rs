@@ -0,0 +1,311 @@
+use crate::*;
+use events::log_privileged_call;
+use near_contract_standards::non_fungible_token::events::NftBurn;
+use near_sdk::{serde_json::json, IntoStorageKey};
+
+/// Time after a failed goal's deadline during which buyers can claim refunds. After it the goal
+/// can be removed, releasing what wasn't claimed.
+const REFUND_PERIOD_MS: TimestampMs = 30 * 24 * 60 * 60 * 1000;
+
+/// Amount which must be raised by `deadline` for the proceeds to be released
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct FundingGoal {
+    pub amount: U128,
+    pub deadline: TimestampMs,
+}
+
+/// State of the funding goal
+#[witgen]
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum FundingStatus {
+    /// There is no funding goal, proceeds are not held
+    NoGoal,
+    /// Proceeds are held until the goal is reached
+    Raising,
+    /// Goal was reached and the proceeds were released
+    Reached,
+    /// Goal was not reached by the deadline, buyers can claim refunds
+    Failed,
+}
+
+/// Information about the funding goal
+#[witgen]
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FundingInfo {
+    pub goal: Option<FundingGoal>,
+    pub status: FundingStatus,
+    /// Proceeds currently held
+    pub held: U128,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Escrow {
+    pub(crate) goal: Option<FundingGoal>,
+    /// Proceeds held until the goal is reached, including those of series
+    pub(crate) held: Balance,
+    /// Part of `held` paid for tokens of each series, released with the series' initial royalties
+    pub(crate) held_by_series: UnorderedMap<SeriesId, Balance>,
+    /// Price paid for each token minted while proceeds are held
+    pub(crate) payments: LookupMap<TokenId, Balance>,
+    pub(crate) reached: bool,
+    /// Set when a failed goal is removed. Its payments are still recorded, so no new goal can be
+    /// set
+    pub(crate) closed: bool,
+}
+
+impl Escrow {
+    pub fn new<S, T>(payments_prefix: S, held_by_series_prefix: T) -> Self
+    where
+        S: IntoStorageKey,
+        T: IntoStorageKey,
+    {
+        Self {
+            goal: None,
+            held: 0,
+            held_by_series: UnorderedMap::new(held_by_series_prefix),
+            payments: LookupMap::new(payments_prefix),
+            reached: false,
+            closed: false,
+        }
+    }
+
+    /// Price paid for a token which is still held, i.e. the goal was neither reached nor removed
+    fn payment(&self, token_id: &TokenId) -> Option<Balance> {
+        if self.reached || self.closed {
+            return None;
+        }
+        self.payments.get(token_id)
+    }
+
+    fn status(&self) -> FundingStatus {
+        match &self.goal {
+            None => FundingStatus::NoGoal,
+            Some(_) if self.reached => FundingStatus::Reached,
+            Some(goal) if goal.deadline < current_time_ms() => FundingStatus::Failed,
+            Some(_) => FundingStatus::Raising,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Information about the funding goal and the proceeds held until it is reached
+    pub fn funding_info(&self) -> FundingInfo {
+        FundingInfo {
+            goal: self.escrow.goal.clone(),
+            status: self.funding_status(),
+            held: self.escrow.held.into(),
+        }
+    }
+
+    /// Price paid for a token which is held until the funding goal is reached
+    pub fn escrowed_payment(&self, token_id: TokenId) -> U128 {
+        self.escrow.payment(&token_id).unwrap_or(0).into()
+    }
+
+    /// Set a goal which must be raised by a deadline for proceeds to be released to the
+    /// owner and initial royalties. Passing no `funding_goal` removes the goal.
+    /// Can only be changed while no proceeds are held, and not once the goal has been reached.
+    /// A failed goal can be removed once its refund period of 30 days has passed, which releases
+    /// the proceeds that weren't refunded, after which no new goal can be set.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_funding_goal(&mut self, funding_goal: Option<FundingGoal>) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_funding_goal",
+            json!({ "funding_goal": funding_goal }),
+        );
+        require!(
+            !self.escrow.reached,
+            "Cannot change the funding goal once it has been reached"
+        );
+        require!(
+            !self.escrow.closed,
+            "Cannot set a funding goal after one has failed"
+        );
+        if let (FundingStatus::Failed, Some(goal)) = (self.funding_status(), &self.escrow.goal) {
+            require!(
+                goal.deadline + REFUND_PERIOD_MS < current_time_ms(),
+                "Refunds can still be claimed"
+            );
+            require!(
+                funding_goal.is_none(),
+                "A failed funding goal can only be removed"
+            );
+            self.escrow.closed = true;
+            self.release_held();
+        }
+        require!(
+            self.escrow.held == 0,
+            "Cannot change the funding goal while proceeds are held"
+        );
+        self.escrow.goal = funding_goal;
+        true
+    }
+
+    /// If the funding goal was not reached, burn the given tokens owned by the caller
+    /// and refund the price paid for them. Their ids go back to the raffle they were drawn from.
+    pub fn claim_refund(&mut self, token_ids: Vec<TokenId>) -> Promise {
+        require!(
+            matches!(self.funding_status(), FundingStatus::Failed),
+            "Refunds are only possible if the funding goal was not reached"
+        );
+        let account_id = env::predecessor_account_id();
+        let amount: Balance = token_ids
+            .iter()
+            .map(|token_id| {
+                let owner_id = self.tokens.owner_by_id.get(token_id);
+                require!(
+                    owner_id.as_ref() == Some(&account_id),
+                    "Only the owner of a token can claim its refund"
+                );
+                let paid = self
+                    .escrow
+                    .payment(token_id)
+                    .unwrap_or_else(|| env::panic_str("No payment held for token"));
+                self.escrow.payments.remove(token_id);
+                if let Some(series_id) = series_of(token_id) {
+                    let held = self.escrow.held_by_series.get(&series_id.to_string());
+                    self.escrow
+                        .held_by_series
+                        .insert(&series_id.to_string(), &(held.unwrap_or(0) - paid));
+                }
+                self.internal_burn(token_id, &account_id);
+                self.return_to_raffle(token_id);
+                paid
+            })
+            .sum();
+        self.escrow.held -= amount;
+        NftBurn {
+            owner_id: &account_id,
+            token_ids: &token_ids.iter().map(String::as_str).collect::<Vec<_>>(),
+            authorized_id: None,
+            memo: Some("refund"),
+        }
+        .emit();
+        Promise::new(account_id).transfer(amount)
+    }
+}
+
+impl Contract {
+    pub(crate) fn funding_status(&self) -> FundingStatus {
+        self.escrow.status()
+    }
+
+    /// Panics if the funding goal was not reached, since the tokens are being refunded
+    pub(crate) fn assert_funding_not_failed(&self) {
+        require!(
+            !matches!(self.funding_status(), FundingStatus::Failed),
+            "Funding goal was not reached"
+        );
+    }
+
+    /// Credit the proceeds of minting `tokens` of a series, or of the main collection when there
+    /// is no `series_id`, or hold them until the funding goal is reached. Either way they are
+    /// split with the initial royalties of the series or main collection.
+    pub(crate) fn receive_proceeds(
+        &mut self,
+        tokens: &[Token],
+        amount: Balance,
+        series_id: Option<&str>,
+    ) {
+        if !matches!(self.funding_status(), FundingStatus::Raising) {
+            self.credit_sale(amount, self.initial_royalties_of(series_id));
+            return;
+        }
+        if amount > 0 {
+            // The first token is also paid the remainder, so refunds add up to `amount`
+            let per_token = amount / tokens.len() as Balance;
+            let remainder = amount % tokens.len() as Balance;
+            tokens.iter().enumerate().for_each(|(i, token)| {
+                let paid = if i == 0 {
+                    per_token + remainder
+                } else {
+                    per_token
+                };
+                self.escrow.payments.insert(&token.token_id, &paid);
+            });
+        }
+        if let Some(series_id) = series_id {
+            let held = self.escrow.held_by_series.get(&series_id.to_string());
+            self.escrow
+                .held_by_series
+                .insert(&series_id.to_string(), &(held.unwrap_or(0) + amount));
+        }
+        self.escrow.held += amount;
+        let goal = self.escrow.goal.as_ref().map_or(0, |goal| goal.amount.0);
+        if self.escrow.held >= goal {
+            log!("Funding goal reached, releasing {}", self.escrow.held);
+            self.escrow.reached = true;
+            self.release_held();
+        }
+    }
+
+    /// Credit all held proceeds, those of each series split with its own initial royalties
+    fn release_held(&mut self) {
+        let mut held = std::mem::take(&mut self.escrow.held);
+        let held_by_series = self.escrow.held_by_series.to_vec();
+        self.escrow.held_by_series.clear();
+        held_by_series.into_iter().for_each(|(series_id, amount)| {
+            held -= amount;
+            if amount > 0 {
+                self.credit_sale(amount, self.initial_royalties_of(Some(&series_id)));
+            }
+        });
+        if held > 0 {
+            self.credit_sale(held, self.sale.initial_royalties.clone());
+        }
+    }
+
+    /// Initial royalties of a series, or of the main collection when there is no `series_id`
+    fn initial_royalties_of(&self, series_id: Option<&str>) -> Option<Royalties> {
+        match series_id {
+            Some(series_id) => self.get_series(series_id).sale.initial_royalties,
+            None => self.sale.initial_royalties.clone(),
+        }
+    }
+
+    /// Put the id of a burned token back in the raffle it was drawn from, so it can be minted again
+    fn return_to_raffle(&mut self, token_id: &str) {
+        let parse_id = |id: &str| {
+            id.parse()
+                .unwrap_or_else(|_| env::panic_str("Invalid token id"))
+        };
+        match token_id.split_once(':') {
+            Some((series_id, id)) => {
+                let mut series = self.get_series(series_id);
+                series.raffle.push(parse_id(id));
+                self.series.insert(&series_id.to_string(), &series);
+            }
+            None => self.raffle.push(parse_id(token_id)),
+        }
+    }
+
+    /// Remove a token and all data associated with it
+    fn internal_burn(&mut self, token_id: &TokenId, owner_id: &AccountId) {
+        self.tokens.owner_by_id.remove(token_id);
+        if let Some(token_metadata_by_id) = &mut self.tokens.token_metadata_by_id {
+            token_metadata_by_id.remove(token_id);
+        }
+        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
+            if let Some(mut token_ids) = tokens_per_owner.get(owner_id) {
+                token_ids.remove(token_id);
+                if token_ids.is_empty() {
+                    tokens_per_owner.remove(owner_id);
+                } else {
+                    tokens_per_owner.insert(owner_id, &token_ids);
+                }
+            }
+        }
+        if let Some(approvals_by_id) = &mut self.tokens.approvals_by_id {
+            approvals_by_id.remove(token_id);
+        }
+        if let Some(next_approval_id_by_id) = &mut self.tokens.next_approval_id_by_id {
+            next_approval_id_by_id.remove(token_id);
+        }
+    }
+}
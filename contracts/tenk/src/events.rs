@@ -0,0 +1,95 @@
+use crate::roles::Role;
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{env, serde::Serialize, serde_json, AccountId};
+
+/// Standard of the events specific to this contract
+const TENK_STANDARD: &str = "tenk";
+const TENK_VERSION: &str = "1.0.0";
+
+/// Log an event following the NEP-297 format,
+/// `EVENT_JSON:{"standard": .., "version": .., "event": .., "data": [..]}`
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct NearEvent<'a, T: Serialize> {
+    standard: &'a str,
+    version: &'a str,
+    event: &'a str,
+    data: &'a [T],
+}
+
+impl<'a, T: Serialize> NearEvent<'a, T> {
+    fn emit(self) {
+        env::log_str(&format!(
+            "EVENT_JSON:{}",
+            serde_json::to_string(&self).unwrap_or_else(|_| env::abort())
+        ));
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct NftMetadataUpdate<'a> {
+    token_ids: &'a [TokenId],
+}
+
+/// Emit that the metadata of `token_ids` has changed.
+pub fn log_metadata_update(token_ids: &[TokenId]) {
+    NearEvent {
+        standard: TENK_STANDARD,
+        version: TENK_VERSION,
+        event: "nft_metadata_update",
+        data: &[NftMetadataUpdate { token_ids }],
+    }
+    .emit()
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct RoleUpdate<'a> {
+    account_id: &'a AccountId,
+    role: Role,
+}
+
+/// Emit that `account_id` was granted `role`.
+pub fn log_role_granted(account_id: &AccountId, role: Role) {
+    NearEvent {
+        standard: TENK_STANDARD,
+        version: TENK_VERSION,
+        event: "role_granted",
+        data: &[RoleUpdate { account_id, role }],
+    }
+    .emit()
+}
+
+/// Emit that `role` was revoked from `account_id`.
+pub fn log_role_revoked(account_id: &AccountId, role: Role) {
+    NearEvent {
+        standard: TENK_STANDARD,
+        version: TENK_VERSION,
+        event: "role_revoked",
+        data: &[RoleUpdate { account_id, role }],
+    }
+    .emit()
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct PrivilegedCall<'a, T: Serialize> {
+    caller: &'a AccountId,
+    args: T,
+}
+
+/// Emit that a privileged `method` was called, along with who called it and its arguments,
+/// e.g. `{"event": "update_price", "data": [{"caller": "alice.near", "args": {"price": ".."}}]}`.
+pub fn log_privileged_call<T: Serialize>(method: &str, args: T) {
+    NearEvent {
+        standard: TENK_STANDARD,
+        version: TENK_VERSION,
+        event: method,
+        data: &[PrivilegedCall {
+            caller: &env::predecessor_account_id(),
+            args,
+        }],
+    }
+    .emit()
+}
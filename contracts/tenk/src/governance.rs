@@ -0,0 +1,157 @@
+use crate::*;
+use events::log_privileged_call;
+use near_sdk::serde_json::json;
+
+/// Methods which, once a `dao` is set, can only be called by it
+const GOVERNED_METHODS: [&str; 11] = [
+    "transfer_ownership",
+    "cancel_ownership_transfer",
+    "update_backup_owner",
+    "add_admin",
+    "remove_admin",
+    "grant_role",
+    "revoke_role",
+    "update_timelock",
+    "update_dao",
+    "upgrade",
+    "update_linkdrop_proxy",
+];
+
+#[near_bindgen]
+impl Contract {
+    /// DAO, e.g. a Sputnik DAO, which takes the place of the owner. None, means the owner acts
+    /// directly
+    pub fn dao(&self) -> Option<AccountId> {
+        self.dao.clone()
+    }
+
+    /// Methods which must be called by the `dao` through a proposal, once it is set.
+    /// The DAO also has every role, while the owner and backup owner no longer do.
+    pub fn governed_methods(&self) -> Vec<String> {
+        GOVERNED_METHODS.iter().map(|m| m.to_string()).collect()
+    }
+
+    /// DAO proposed with `update_dao`, which takes the owner's permissions once it calls
+    /// `accept_dao`
+    pub fn pending_dao(&self) -> Option<AccountId> {
+        self.pending_dao.clone()
+    }
+
+    /// Propose a DAO to take the owner's permissions, which it does once it calls `accept_dao`.
+    /// After that only proposals executed by it can call the `governed_methods`, including this
+    /// one. Passing no `dao` cancels any proposal and hands the permissions back to the owner.
+    /// @allow ["::owner"]
+    pub fn update_dao(&mut self, dao: Option<AccountId>) -> bool {
+        self.assert_owner();
+        log_privileged_call("update_dao", json!({ "dao": dao }));
+        if dao.is_none() {
+            self.dao = None;
+        }
+        self.pending_dao = dao;
+        true
+    }
+
+    /// Take the owner's permissions. Can only be called by the DAO proposed with `update_dao`
+    pub fn accept_dao(&mut self) -> bool {
+        let dao = env::predecessor_account_id();
+        require!(
+            self.pending_dao.as_ref() == Some(&dao),
+            "Method is private to the proposed DAO"
+        );
+        log_privileged_call("accept_dao", json!({}));
+        self.pending_dao = None;
+        self.dao = Some(dao);
+        true
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils::VMContextBuilder, testing_env};
+
+    fn owner() -> AccountId {
+        AccountId::new_unchecked("owner.near".to_string())
+    }
+
+    fn stranger() -> AccountId {
+        AccountId::new_unchecked("stranger.near".to_string())
+    }
+
+    fn dao() -> AccountId {
+        AccountId::new_unchecked("dao.near".to_string())
+    }
+
+    fn called_by(account_id: AccountId) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .build());
+    }
+
+    /// Contract owned by `owner()` which has proposed `dao()`, with the following calls made by
+    /// the owner
+    fn dao_proposed() -> Contract {
+        called_by(owner());
+        let mut contract = Contract::new_default_meta(
+            owner(),
+            InitialMetadata {
+                name: "name".to_string(),
+                symbol: "sym".to_string(),
+                uri: "https://".to_string(),
+                ..Default::default()
+            },
+            100,
+            None,
+            None,
+            None,
+            None,
+        );
+        contract.update_dao(Some(dao()));
+        contract
+    }
+
+    /// Contract owned by `owner()` governed by `dao()`, with the following calls made by the DAO
+    fn governed_by_dao() -> Contract {
+        let mut contract = dao_proposed();
+        called_by(dao());
+        contract.accept_dao();
+        contract
+    }
+
+    #[test]
+    fn proposed_dao_must_accept() {
+        let contract = dao_proposed();
+        assert_eq!(contract.pending_dao(), Some(dao()));
+        assert!(contract.is_owner(&owner()));
+        assert!(!contract.is_owner(&dao()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is private to the proposed DAO")]
+    fn stranger_cannot_accept_dao() {
+        let mut contract = dao_proposed();
+        called_by(stranger());
+        contract.accept_dao();
+    }
+
+    #[test]
+    #[should_panic(expected = "Method is private to owner")]
+    fn owner_cannot_act_once_governed_by_dao() {
+        let mut contract = governed_by_dao();
+        called_by(owner());
+        contract.add_admin(stranger());
+    }
+
+    #[test]
+    fn dao_acts_as_owner() {
+        let dao = dao();
+        let mut contract = governed_by_dao();
+        contract.add_admin(stranger());
+        contract.update_price(U128(5));
+        contract.update_dao(None);
+        assert!(contract.is_admin(&stranger()));
+        assert_eq!(contract.sale.price.0, 5);
+        assert!(!contract.is_owner(&dao));
+    }
+}
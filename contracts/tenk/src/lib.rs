@@ -1,17 +1,17 @@
-use linkdrop::LINKDROP_DEPOSIT;
+use linkdrop::{Linkdrop, LINKDROP_DEPOSIT};
 use near_contract_standards::non_fungible_token::{
     metadata::{NFTContractMetadata, TokenMetadata, NFT_METADATA_SPEC},
     refund_deposit_to_account, NonFungibleToken, Token, TokenId,
 };
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
-    collections::{LazyOption, LookupMap, UnorderedSet},
+    collections::{LazyOption, LookupMap, LookupSet, UnorderedMap, UnorderedSet, Vector},
     env, ext_contract,
     json_types::{Base64VecU8, U128},
     log, near_bindgen, require,
     serde::{Deserialize, Serialize},
     witgen, AccountId, Balance, BorshStorageKey, Gas, PanicOnDefault, Promise, PromiseOrValue,
-    PublicKey,
+    PublicKey, StorageUsage,
 };
 use near_units::{parse_gas, parse_near};
 
@@ -19,18 +19,38 @@ use near_units::{parse_gas, parse_near};
 #[witgen]
 type TimestampMs = u64;
 
+mod escrow;
+mod events;
+mod governance;
 pub mod linkdrop;
+mod migrate;
 mod owner;
+mod passes;
+mod pause;
 pub mod payout;
+mod proceeds;
 mod raffle;
+mod referral;
+mod roles;
+mod series;
 mod standards;
+mod stats;
+mod timelock;
+mod token_metadata;
 mod types;
 mod util;
 mod views;
 
+use escrow::*;
+use pause::*;
 use payout::*;
 use raffle::Raffle;
+use referral::*;
+use roles::*;
+use series::*;
 use standards::*;
+use stats::*;
+use timelock::*;
 use types::*;
 use util::{current_time_ms, is_promise_success, log_mint, refund};
 
@@ -42,7 +62,8 @@ pub struct Contract {
     /// Vector of available NFTs
     raffle: Raffle,
     pending_tokens: u32,
-    /// Linkdrop fields will be removed once proxy contract is deployed
+    /// Keys of linkdrops created before the linkdrop proxy, which are access keys of this
+    /// contract, and whether their token is minted for free
     pub accounts: LookupMap<PublicKey, bool>,
     /// Whitelist
     whitelist: LookupMap<AccountId, Allowance>,
@@ -53,11 +74,81 @@ pub struct Contract {
 
     /// extension for generating media links
     media_extension: Option<String>,
+
+    /// Accounts, e.g. game contracts, allowed to update the mutable metadata of any token
+    metadata_updaters: UnorderedSet<AccountId>,
+
+    /// Additional collections, each with their own raffle and sale
+    series: UnorderedMap<SeriesId, Series>,
+
+    /// Royalties overriding the collection's for specific tokens
+    token_royalties: LookupMap<TokenId, Royalties>,
+    /// Royalties overriding the collection's for ranges of token ids
+    royalty_ranges: Vector<RoyaltyRange>,
+
+    /// Fee taken by the platform from royalties
+    platform_fee: PlatformFee,
+
+    /// Proceeds from minting which each account can withdraw
+    proceeds: LookupMap<AccountId, Balance>,
+
+    /// Proceeds held until an optional funding goal is reached
+    escrow: Escrow,
+
+    /// Totals of all sales
+    sale_stats: LazyOption<SaleStats>,
+    /// Totals of each account's mints
+    account_stats: LookupMap<AccountId, AccountStats>,
+
+    /// Number of tokens each account has minted from the main collection, across all phases
+    minted: LookupMap<AccountId, u16>,
+
+    /// Contracts, e.g. a sales contract, allowed to mint on behalf of users
+    trusted_minters: UnorderedSet<AccountId>,
+
+    /// Passes held by each account, each redeemable for one reserved token
+    passes: LookupMap<AccountId, u32>,
+    /// NFT contract whose tokens are exchanged for passes
+    pass_contract: Option<AccountId>,
+
+    /// Commission paid to accounts referring minters
+    referral_program: Option<ReferralProgram>,
+    /// Tokens referred and commission earned by each referrer
+    referrals: LookupMap<AccountId, ReferralStats>,
+
+    /// Roles granted to accounts which aren't the owner or an admin
+    roles: LookupMap<AccountId, Vec<Role>>,
+
+    /// Account proposed as the new owner, which must accept the transfer
+    pending_owner: Option<AccountId>,
+    /// Account with the same permissions as the owner, e.g. to recover the contract
+    backup_owner: Option<AccountId>,
+
+    /// Parts of the contract paused in an emergency
+    paused: Vec<PauseScope>,
+
+    /// Delay in ms before sensitive configuration changes take effect
+    timelock: Option<u64>,
+    pending_changes: Vec<PendingChange>,
+    next_change_id: u32,
+
+    /// DAO which takes the place of the owner when set
+    dao: Option<AccountId>,
+
+    /// Linkdrop proxy holding the keys of linkdrops
+    linkdrop_proxy: Option<AccountId>,
+    /// Keys of linkdrops held by the linkdrop proxy with a token reserved
+    linkdrops: LookupMap<PublicKey, Linkdrop>,
+
+    /// DAO proposed to take the place of the owner, which must accept it
+    pending_dao: Option<AccountId>,
+    /// Number of `linkdrops`, which must be claimed or deleted before changing the proxy
+    pending_linkdrops: u32,
+    /// Pairs of a receiver and a payer allowed to mint to it using its allowance
+    gift_approvals: LookupSet<(AccountId, AccountId)>,
 }
 
-const GAS_REQUIRED_FOR_LINKDROP: Gas = Gas(parse_gas!("40 Tgas") as u64);
 const GAS_REQUIRED_TO_CREATE_LINKDROP: Gas = Gas(parse_gas!("20 Tgas") as u64);
-const TECH_BACKUP_OWNER: &str = "willem.near";
 const MAX_DATE: u64 = 8640000000000000;
 // const GAS_REQUIRED_FOR_LINKDROP_CALL: Gas = Gas(5_000_000_000_000);
 
@@ -70,9 +161,11 @@ trait Linkdrop {
         gas_required: Gas,
     ) -> Promise;
 
-    fn on_send_with_callback(&mut self) -> Promise;
+    fn on_send_with_callback(&mut self, account_id: AccountId, public_key: PublicKey) -> Promise;
+
+    fn on_linkdrop_deleted(&mut self, public_key: PublicKey) -> bool;
 
-    fn link_callback(&mut self, account_id: AccountId, mint_for_free: bool) -> Token;
+    fn on_withdraw(&mut self, account_id: AccountId, amount: U128);
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -86,6 +179,26 @@ enum StorageKey {
     LinkdropKeys,
     Whitelist,
     Admins,
+    MetadataUpdaters,
+    Series,
+    SeriesRaffle { series_id: SeriesId },
+    SeriesMinted { series_id: SeriesId },
+    TokenRoyalties,
+    RoyaltyRanges,
+    Proceeds,
+    EscrowPayments,
+    SaleStats,
+    AccountStats,
+    Minted,
+    TrustedMinters,
+    Passes,
+    Referrals,
+    Roles,
+    StateVersion,
+    Linkdrops,
+    EscrowHeldBySeries,
+    StagedCode,
+    GiftApprovals,
 }
 
 #[near_bindgen]
@@ -97,6 +210,8 @@ impl Contract {
         size: u32,
         sale: Option<Sale>,
         media_extension: Option<String>,
+        platform_fee: Option<PlatformFee>,
+        backup_owner: Option<AccountId>,
     ) -> Self {
         Self::new(
             owner_id,
@@ -104,6 +219,8 @@ impl Contract {
             size,
             sale.unwrap_or_default(),
             media_extension,
+            platform_fee,
+            backup_owner,
         )
     }
 
@@ -114,15 +231,20 @@ impl Contract {
         size: u32,
         sale: Sale,
         media_extension: Option<String>,
+        platform_fee: Option<PlatformFee>,
+        backup_owner: Option<AccountId>,
     ) -> Self {
         metadata.assert_valid();
         sale.validate();
+        let platform_fee = platform_fee.unwrap_or_default();
+        platform_fee.validate();
         if let Some(ext) = media_extension.as_ref() {
             require!(
                 !ext.starts_with('.'),
                 "media extension must not start with '.'"
             );
         }
+        Self::write_state_version();
         Self {
             tokens: NonFungibleToken::new(
                 StorageKey::NonFungibleToken,
@@ -139,6 +261,34 @@ impl Contract {
             sale,
             admins: UnorderedSet::new(StorageKey::Admins),
             media_extension,
+            metadata_updaters: UnorderedSet::new(StorageKey::MetadataUpdaters),
+            series: UnorderedMap::new(StorageKey::Series),
+            token_royalties: LookupMap::new(StorageKey::TokenRoyalties),
+            royalty_ranges: Vector::new(StorageKey::RoyaltyRanges),
+            platform_fee,
+            proceeds: LookupMap::new(StorageKey::Proceeds),
+            escrow: Escrow::new(StorageKey::EscrowPayments, StorageKey::EscrowHeldBySeries),
+            sale_stats: LazyOption::new(StorageKey::SaleStats, None),
+            account_stats: LookupMap::new(StorageKey::AccountStats),
+            minted: LookupMap::new(StorageKey::Minted),
+            trusted_minters: UnorderedSet::new(StorageKey::TrustedMinters),
+            passes: LookupMap::new(StorageKey::Passes),
+            pass_contract: None,
+            referral_program: None,
+            referrals: LookupMap::new(StorageKey::Referrals),
+            roles: LookupMap::new(StorageKey::Roles),
+            pending_owner: None,
+            backup_owner,
+            paused: Vec::new(),
+            timelock: None,
+            pending_changes: Vec::new(),
+            next_change_id: 0,
+            dao: None,
+            linkdrop_proxy: None,
+            linkdrops: LookupMap::new(StorageKey::Linkdrops),
+            pending_dao: None,
+            pending_linkdrops: 0,
+            gift_approvals: LookupSet::new(StorageKey::GiftApprovals),
         }
     }
 
@@ -154,134 +304,227 @@ impl Contract {
 
     #[payable]
     pub fn nft_mint_one(&mut self) -> Token {
-        self.nft_mint_many(1)[0].clone()
+        self.nft_mint_many(1, None)[0].clone()
     }
 
+    /// Mint `num` tokens to the caller.
+    /// If there is a referral program, the `referrer` is credited a commission from the price.
     #[payable]
-    pub fn nft_mint_many(&mut self, num: u16) -> Vec<Token> {
-        if let Some(limit) = self.sale.mint_rate_limit {
-            require!(num <= limit, "over mint limit");
-        }
-        let owner_id = &env::signer_account_id();
-        let num = self.assert_can_mint(owner_id, num);
-        let tokens = self.nft_mint_many_ungaurded(num, owner_id, false);
-        self.use_whitelist_allowance(owner_id, num);
-        tokens
+    pub fn nft_mint_many(&mut self, num: u16, referrer: Option<AccountId>) -> Vec<Token> {
+        let owner_id = &env::predecessor_account_id();
+        self.mint_for(owner_id, owner_id, owner_id, num, referrer.as_ref())
+    }
+
+    /// Mint `num` tokens to `receiver_id`, paid for by the caller, e.g. as a gift.
+    /// The sale's `gift_allowance` decides whether the caller's or the receiver's allowance is used.
+    /// The receiver's allowance can only be used if it has approved the caller with `approve_gifts`.
+    #[payable]
+    pub fn nft_mint_to(&mut self, receiver_id: AccountId, num: u16) -> Vec<Token> {
+        let payer = &env::predecessor_account_id();
+        let allowance_holder = match self.sale.gift_allowance.unwrap_or_default() {
+            GiftAllowance::Payer => payer,
+            GiftAllowance::Receiver => {
+                require!(
+                    self.gifts_approved(receiver_id.clone(), payer.clone()),
+                    "Receiver has not approved gifts from caller"
+                );
+                &receiver_id
+            }
+        };
+        self.mint_for(payer, &receiver_id, allowance_holder, num, None)
+    }
+
+    /// Allow `payer_id` to mint tokens to the caller with `nft_mint_to` using the caller's
+    /// allowance, when the sale's `gift_allowance` is the receiver's.
+    /// Attached deposit must cover the storage of the approval, the rest is refunded.
+    #[payable]
+    pub fn approve_gifts(&mut self, payer_id: AccountId) -> bool {
+        let receiver_id = env::predecessor_account_id();
+        let initial_storage_usage = env::storage_usage();
+        self.gift_approvals.insert(&(receiver_id.clone(), payer_id));
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        self.charge_storage(&receiver_id, storage_used);
+        true
+    }
+
+    /// Stop `payer_id` from using the caller's allowance to mint to it
+    pub fn revoke_gifts(&mut self, payer_id: AccountId) -> bool {
+        self.gift_approvals
+            .remove(&(env::predecessor_account_id(), payer_id))
+    }
+
+    /// Whether `payer_id` can use the allowance of `receiver_id` to mint to it
+    pub fn gifts_approved(&self, receiver_id: AccountId, payer_id: AccountId) -> bool {
+        receiver_id == payer_id || self.gift_approvals.contains(&(receiver_id, payer_id))
     }
 
-    fn nft_mint_many_ungaurded(
+    /// Mint `num` tokens to `account_id` as if it minted them itself, paid for by the caller.
+    /// Can only be called by a trusted minter, e.g. a sales contract.
+    #[payable]
+    pub fn nft_mint_on_behalf(&mut self, account_id: AccountId, num: u16) -> Vec<Token> {
+        let payer = &env::predecessor_account_id();
+        require!(
+            self.trusted_minters.contains(payer),
+            "Method is private to trusted minters"
+        );
+        self.mint_for(payer, &account_id, &account_id, num, None)
+    }
+
+    /// Mint `num` tokens to `receiver_id`, checking and using the allowance of `allowance_holder`.
+    /// `payer` pays the price and storage, and is refunded the rest of the attached deposit.
+    fn mint_for(
         &mut self,
+        payer: &AccountId,
+        receiver_id: &AccountId,
+        allowance_holder: &AccountId,
         num: u16,
-        owner_id: &AccountId,
-        mint_for_free: bool,
+        referrer: Option<&AccountId>,
     ) -> Vec<Token> {
-        let initial_storage_usage = if mint_for_free {
-            0
-        } else {
-            env::storage_usage()
-        };
+        self.assert_not_paused(PauseScope::Mint);
+        self.sale.assert_within_rate_limit(num);
+        let num = self.assert_can_mint(payer, allowance_holder, num);
+        // Price must be found before minting since the sale's status could change
+        let status = self.get_status();
+        let price = self.price_of(num, payer);
+        let initial_storage_usage = env::storage_usage();
+        let tokens = self.nft_mint_many_ungaurded(num, receiver_id);
+        if !self.is_owner(payer) {
+            self.use_allowance(allowance_holder, num);
+        }
+        self.record_sale(payer, num, price, &status);
+        let commission =
+            referrer.map_or(0, |referrer| self.pay_referrer(referrer, payer, num, price));
+        self.receive_proceeds(&tokens, price - commission, None);
+        let storage_used = env::storage_usage() - initial_storage_usage;
+        self.pay_for_mint(payer, price, storage_used);
+        tokens
+    }
 
+    fn nft_mint_many_ungaurded(&mut self, num: u16, owner_id: &AccountId) -> Vec<Token> {
         // Mint tokens
         let tokens: Vec<Token> = (0..num)
             .map(|_| self.draw_and_mint(owner_id.clone(), None))
             .collect();
 
-        if !mint_for_free {
-            let storage_used = env::storage_usage() - initial_storage_usage;
-            if let Some(royalties) = &self.sale.initial_royalties {
-                // Keep enough funds to cover storage and split the rest as royalties
-                let storage_cost = env::storage_byte_cost() * storage_used as Balance;
-                let left_over_funds = env::attached_deposit() - storage_cost;
-                royalties.send_funds(left_over_funds, &self.tokens.owner_id);
-            } else {
-                // Keep enough funds to cover storage and send rest to contract owner
-                refund_deposit_to_account(storage_used, self.tokens.owner_id.clone());
-            }
-        }
         // Emit mint event log
         log_mint(owner_id, &tokens);
         tokens
     }
 
+    /// Keep the price of the tokens and the cost of the storage used, refunding the rest to `payer`
+    fn pay_for_mint(&self, payer: &AccountId, price: Balance, storage_used: StorageUsage) {
+        let storage_cost = env::storage_byte_cost() * storage_used as Balance;
+        let total_cost = price + storage_cost;
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= total_cost,
+            format!(
+                "Must attach {} yoctoNEAR to cover price and storage",
+                total_cost
+            )
+        );
+        refund(payer, deposit - total_cost);
+    }
+
+    /// Keep the cost of the storage used by a call which isn't a mint, refunding the rest to `payer`
+    fn charge_storage(&self, payer: &AccountId, storage_used: StorageUsage) {
+        let storage_cost = env::storage_byte_cost() * storage_used as Balance;
+        let deposit = env::attached_deposit();
+        require!(
+            deposit >= storage_cost,
+            format!("Must attach {} yoctoNEAR to cover storage", storage_cost)
+        );
+        refund(payer, deposit - storage_cost);
+    }
+
+    /// Credit the proceeds of a sale to the contract owner or split them as royalties
+    fn credit_sale(&mut self, amount: Balance, initial_royalties: Option<Royalties>) {
+        let owner_id = self.tokens.owner_id.clone();
+        let payout = initial_royalties.map_or_else(
+            || Payout::to_owner(amount, &owner_id),
+            |royalties| royalties.create_payout(amount, &owner_id, &self.platform_fee),
+        );
+        let royalties_paid = payout
+            .payout
+            .iter()
+            .filter(|(account_id, _)| **account_id != owner_id)
+            .map(|(_, amount)| amount.0)
+            .sum();
+        self.record_royalties_paid(royalties_paid);
+        self.credit_payout(payout);
+    }
+
     // Contract private methods
 
+    /// Release the token reserved for a linkdrop if the linkdrop proxy failed to add its key,
+    /// refunding what was paid for it
     #[private]
     #[payable]
-    pub fn on_send_with_callback(&mut self) {
+    pub fn on_send_with_callback(&mut self, account_id: AccountId, public_key: PublicKey) {
         if !is_promise_success(None) {
-            self.pending_tokens -= 1;
-            let amount = env::attached_deposit();
-            if amount > 0 {
-                refund(&env::signer_account_id(), amount);
-            }
-        }
-    }
-
-    #[payable]
-    #[private]
-    pub fn link_callback(&mut self, account_id: AccountId, mint_for_free: bool) -> Token {
-        if is_promise_success(None) {
-            self.pending_tokens -= 1;
-            self.nft_mint_many_ungaurded(1, &account_id, mint_for_free)[0].clone()
-        } else {
-            env::panic_str("Promise before Linkdrop callback failed");
+            self.take_linkdrop(&public_key);
+            // The deposit attached to the linkdrop proxy is refunded to this contract
+            refund(&account_id, env::attached_deposit() + LINKDROP_DEPOSIT);
         }
     }
 
     // Private methods
     fn assert_deposit(&self, num: u16, account_id: &AccountId) {
         require!(
-            env::attached_deposit() >= self.total_cost(num, account_id).0,
+            env::attached_deposit() >= self.mint_deposit(num, account_id).0,
             "Not enough attached deposit to buy"
         );
     }
 
-    fn assert_can_mint(&mut self, account_id: &AccountId, num: u16) -> u16 {
+    /// Number of tokens `payer` can mint, using the allowance of `allowance_holder`
+    fn assert_can_mint(
+        &mut self,
+        payer: &AccountId,
+        allowance_holder: &AccountId,
+        num: u16,
+    ) -> u16 {
+        self.assert_funding_not_failed();
         let mut num = num;
         // Check quantity
         // Owner can mint for free
-        if !self.is_owner(account_id) {
-            let allowance = match self.get_status() {
+        if !self.is_owner(payer) {
+            match self.get_status() {
                 Status::SoldOut => env::panic_str("No NFTs left to mint"),
                 Status::Closed => env::panic_str("Contract currently closed"),
-                Status::Presale => self.get_whitelist_allowance(account_id).left(),
-                Status::Open => self.get_or_add_whitelist_allowance(account_id, num),
+                Status::Presale => {
+                    require!(
+                        self.whitelisted(allowance_holder),
+                        "Account not on whitelist"
+                    )
+                }
+                Status::Open => (),
             };
-            num = u16::min(allowance, num);
+            if let Some(allowance) = self.remaining_allowance(allowance_holder) {
+                num = u16::min(allowance, num);
+            }
             require!(num > 0, "Account has no more allowance left");
         }
-        require!(self.tokens_left() >= num as u32, "No NFTs left to mint");
-        self.assert_deposit(num, account_id);
+        // Only mint what is left
+        num = u32::min(self.tokens_left(), num as u32) as u16;
+        require!(num > 0, "No NFTs left to mint");
+        self.assert_deposit(num, payer);
         num
     }
 
     fn assert_owner(&self) {
-        require!(self.signer_is_owner(), "Method is private to owner")
+        require!(self.caller_is_owner(), "Method is private to owner")
     }
 
-    fn signer_is_owner(&self) -> bool {
-        self.is_owner(&env::signer_account_id())
+    fn caller_is_owner(&self) -> bool {
+        self.is_owner(&env::predecessor_account_id())
     }
 
+    /// Whether an account has the owner's permissions, which is only the `dao` once one is set
     fn is_owner(&self, minter: &AccountId) -> bool {
-        minter.as_str() == self.tokens.owner_id.as_str() || minter.as_str() == TECH_BACKUP_OWNER
-    }
-
-    fn assert_owner_or_admin(&self) {
-        require!(
-            self.signer_is_owner_or_admin(),
-            "Method is private to owner or admin"
-        )
-    }
-
-    #[allow(dead_code)]
-    fn signer_is_admin(&self) -> bool {
-        self.is_admin(&env::signer_account_id())
-    }
-
-    fn signer_is_owner_or_admin(&self) -> bool {
-        let signer = env::signer_account_id();
-        self.is_owner(&signer) || self.is_admin(&signer)
+        match &self.dao {
+            Some(dao) => minter == dao,
+            None => minter == &self.tokens.owner_id || self.backup_owner.as_ref() == Some(minter),
+        }
     }
 
     fn is_admin(&self, account_id: &AccountId) -> bool {
@@ -341,35 +584,27 @@ impl Contract {
         }
     }
 
-    fn use_whitelist_allowance(&mut self, account_id: &AccountId, num: u16) {
-        if self.has_allowance() && !self.is_owner(account_id) {
-            let mut allowance = self.get_whitelist_allowance(account_id);
-            allowance.use_num(num);
-            self.whitelist.insert(account_id, &allowance);
+    /// Count `num` tokens towards an account's lifetime mints and, during the presale,
+    /// its whitelist allowance
+    fn use_allowance(&mut self, account_id: &AccountId, num: u16) {
+        if self.is_owner(account_id) {
+            return;
+        }
+        self.minted
+            .insert(account_id, &(self.minted_by(account_id) + num));
+        if self.is_presale() {
+            if let Some(mut allowance) = self.whitelist.get(account_id) {
+                allowance.use_num(num);
+                self.whitelist.insert(account_id, &allowance);
+            }
         }
     }
 
-    fn get_whitelist_allowance(&self, account_id: &AccountId) -> Allowance {
-        self.whitelist
-            .get(account_id)
-            .unwrap_or_else(|| panic!("Account not on whitelist"))
-    }
-
-    fn get_or_add_whitelist_allowance(&mut self, account_id: &AccountId, num: u16) -> u16 {
-        // return num if allowance isn't set
-        self.sale.allowance.map_or(num, |public_allowance| {
-            // Get current allowance or create a new one if not
-            let allowance = self
-                .whitelist
-                .get(account_id)
-                .unwrap_or_else(|| Allowance::new(public_allowance))
-                .raise_max(public_allowance);
-            self.whitelist.insert(account_id, &allowance);
-            allowance.left()
-        })
-    }
-    fn has_allowance(&self) -> bool {
-        self.sale.allowance.is_some() || self.is_presale()
+    /// Tokens minted by an account. Contracts migrated from `ContractV1` only counted them in the
+    /// whitelist, so that is used until the account mints again
+    fn minted_by(&self, account_id: &AccountId) -> u16 {
+        let whitelist_used = self.whitelist.get(account_id).map_or(0, |a| a.used());
+        u16::max(self.minted.get(account_id).unwrap_or(0), whitelist_used)
     }
 
     fn is_presale(&self) -> bool {
@@ -377,22 +612,19 @@ impl Contract {
     }
 
     fn get_status(&self) -> Status {
-        if self.tokens_left() == 0 {
-            return Status::SoldOut;
-        }
-        let current_time = current_time_ms();
-        match (self.sale.presale_start, self.sale.public_sale_start) {
-            (_, Some(public)) if public < current_time => Status::Open,
-            (Some(pre), _) if pre < current_time => Status::Presale,
-            (_, _) => Status::Closed,
-        }
+        self.sale.status(self.tokens_left())
     }
 
     fn price(&self) -> u128 {
-        match self.get_status() {
-            Status::Presale | Status::Closed => self.sale.presale_price.unwrap_or(self.sale.price),
-            Status::Open | Status::SoldOut => self.sale.price,
+        self.sale.price(&self.get_status())
+    }
+
+    /// Price of `num` tokens, not including storage
+    fn price_of(&self, num: u16, minter: &AccountId) -> Balance {
+        if self.is_owner(minter) {
+            0
+        } else {
+            self.sale.cost(num, &self.get_status())
         }
-        .into()
     }
 }
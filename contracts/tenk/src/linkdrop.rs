@@ -1,148 +1,176 @@
+//! Tokens given away with links are reserved here and claimed through a linkdrop proxy, the
+//! `linkdrop-proxy` contract, which holds the links' keys and calls `on_linkdrop_claim` when one
+//! is claimed.
 use crate::*;
+use events::log_privileged_call;
 use near_sdk::{
-    env, ext_contract, json_types::U128, log, near_bindgen, AccountId, Balance, Gas, Promise,
-    PublicKey,
+    env, ext_contract, log, near_bindgen, serde_json::json, AccountId, Gas, Promise, PublicKey,
 };
 use near_units::parse_near;
 
-/// 0.064311394105062020653824 N
-pub(crate) const ACCESS_KEY_ALLOWANCE: u128 = parse_near!("0 N");
-
+/// Sent to the linkdrop proxy, which gives it to the claiming account or uses it to create it
+/// once the token has been minted
 pub(crate) const LINKDROP_DEPOSIT: u128 = parse_near!("0.02 N");
-/// can take 0.5 of access key since gas required is 6.6 times what was actually used
-const ON_CREATE_ACCOUNT_GAS: Gas = Gas(30_000_000_000_000);
-const NO_DEPOSIT: Balance = 0;
 
-/// Gas attached to the callback from account creation.
-pub const ON_CREATE_ACCOUNT_CALLBACK_GAS: Gas = Gas(10_000_000_000_000);
+/// Gas attached to the linkdrop proxy's `create_linkdrop`
+const GAS_FOR_PROXY_CREATE_LINKDROP: Gas = Gas(10_000_000_000_000);
+/// Gas attached to the linkdrop proxy's `delete_linkdrop`
+const GAS_FOR_PROXY_DELETE_LINKDROP: Gas = Gas(10_000_000_000_000);
+/// Gas attached to `on_linkdrop_deleted`
+const GAS_FOR_ON_LINKDROP_DELETED: Gas = Gas(5_000_000_000_000);
+
+/// Token reserved for a linkdrop held by the linkdrop proxy, which is sold when it is claimed
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Linkdrop {
+    /// Account which created the linkdrop and paid for the token
+    pub(crate) payer: AccountId,
+    /// Price paid for the token
+    pub(crate) price: Balance,
+    /// Status of the sale when the linkdrop was created
+    pub(crate) status: Status,
+}
 
-#[ext_contract(ext_linkdrop)]
-trait ExtLinkdrop {
-    fn create_account(&mut self, new_account_id: AccountId, new_public_key: PublicKey) -> Promise;
-    fn on_create_and_claim(&mut self, mint_for_free: bool) -> bool;
+#[ext_contract(ext_linkdrop_proxy)]
+trait LinkdropProxy {
+    fn create_linkdrop(&mut self, public_key: PublicKey) -> Promise;
+    fn delete_linkdrop(&mut self, public_key: PublicKey) -> Promise;
 }
 
 #[near_bindgen]
 impl Contract {
-    /// Allows given public key to claim sent balance.
-    /// Takes ACCESS_KEY_ALLOWANCE as fee from deposit to cover account creation via an access key.
-
-    /// Claim tokens for specific account that are attached to the public key this tx is signed with.
-    #[private]
-    pub fn claim(&mut self, account_id: AccountId) -> Promise {
-        // require!(false, "Cannot claim at this time try again later");
-        let (mint_for_free, deletion_promise) = self.delete_current_access_key();
-        deletion_promise
-            .then(Promise::new(account_id.clone()).transfer(LINKDROP_DEPOSIT))
-            .then(ext_self::link_callback(
-                account_id.clone(),
-                mint_for_free,
-                env::current_account_id(),
-                self.total_cost(1, &account_id).0,
-                GAS_REQUIRED_FOR_LINKDROP,
-            ))
-            .then(ext_linkdrop::on_create_and_claim(
-                mint_for_free,
-                env::current_account_id(),
-                NO_DEPOSIT,
-                ON_CREATE_ACCOUNT_CALLBACK_GAS,
-            ))
+    /// Linkdrop proxy holding the keys of linkdrops, which calls `on_linkdrop_claim` when one is
+    /// claimed
+    pub fn linkdrop_proxy(&self) -> Option<AccountId> {
+        self.linkdrop_proxy.clone()
     }
 
-    /// Create new account and and claim tokens to it.
-    #[private]
-    pub fn create_account_and_claim(
-        &mut self,
-        new_account_id: AccountId,
-        new_public_key: PublicKey,
-    ) -> Promise {
-        // require!(false, "Cannot claim at this time try again later");
-        let (mint_for_free, deletion_promise) = self.delete_current_access_key();
-        deletion_promise
-            .and(self.create_account(new_account_id.clone(), new_public_key))
-            .then(ext_self::link_callback(
-                new_account_id.clone(),
-                mint_for_free,
-                env::current_account_id(),
-                self.total_cost(1, &new_account_id).0,
-                GAS_REQUIRED_FOR_LINKDROP,
-            ))
-            .then(ext_linkdrop::on_create_and_claim(
-                mint_for_free,
-                env::current_account_id(),
-                NO_DEPOSIT,
-                ON_CREATE_ACCOUNT_CALLBACK_GAS,
-            ))
+    /// Whether a token is reserved for the linkdrop of a key
+    pub fn check_key(&self, public_key: PublicKey) -> bool {
+        self.linkdrops.contains_key(&public_key) || self.accounts.contains_key(&public_key)
     }
 
-    /// Returns the balance associated with given key.
-    #[allow(unused_variables)]
-    pub fn get_key_balance(&self) -> U128 {
-        LINKDROP_DEPOSIT.into()
+    /// Set the linkdrop proxy. Can only be changed once all linkdrops created with the current
+    /// proxy have been claimed or deleted.
+    /// @allow ["::owner"]
+    pub fn update_linkdrop_proxy(&mut self, linkdrop_proxy: Option<AccountId>) -> bool {
+        self.assert_owner();
+        log_privileged_call(
+            "update_linkdrop_proxy",
+            json!({ "linkdrop_proxy": linkdrop_proxy }),
+        );
+        require!(
+            self.pending_linkdrops == 0,
+            "Cannot change the linkdrop proxy while it holds linkdrops"
+        );
+        self.linkdrop_proxy = linkdrop_proxy;
+        true
     }
 
-    pub fn check_key(&self, public_key: PublicKey) -> bool {
-        self.accounts.contains_key(&public_key)
+    /// Mint the token reserved for the linkdrop of `public_key` to `account_id`.
+    /// Called by the linkdrop proxy once the key has been deleted, before the account is created or
+    /// sent the linkdrop deposit. Returns None while linkdrops are paused, so the proxy adds the
+    /// key back.
+    pub fn on_linkdrop_claim(
+        &mut self,
+        public_key: PublicKey,
+        account_id: AccountId,
+    ) -> Option<Token> {
+        require!(
+            self.linkdrop_proxy.as_ref() == Some(&env::predecessor_account_id()),
+            "Method is private to the linkdrop proxy"
+        );
+        if self.paused.contains(&PauseScope::Linkdrop) {
+            log!("Linkdrop is paused");
+            return None;
+        }
+        // Price and storage were paid for when the linkdrop was created
+        let linkdrop = self
+            .take_linkdrop(&public_key)
+            .unwrap_or_else(|| env::panic_str("No linkdrop for key"));
+        let token = self.nft_mint_many_ungaurded(1, &account_id)[0].clone();
+        self.record_sale(&linkdrop.payer, 1, linkdrop.price, &linkdrop.status);
+        if linkdrop.price > 0 {
+            self.receive_proceeds(&[token.clone()], linkdrop.price, None);
+        }
+        Some(token)
     }
 
+    /// Release the token reserved for the linkdrop of `public_key` once the linkdrop proxy has
+    /// deleted it. If it couldn't, e.g. because the linkdrop is being claimed, it is kept.
     #[private]
-    pub fn on_create_and_claim(&mut self, mint_for_free: bool) {
+    pub fn on_linkdrop_deleted(&mut self, public_key: PublicKey) -> bool {
         if !is_promise_success(None) {
-            self.send(env::signer_account_pk(), mint_for_free);
+            log!("Linkdrop proxy could not delete linkdrop");
+            return false;
         }
-    }
-
-    pub fn get_linkdrop_contract(&self) -> AccountId {
-        AccountId::new_unchecked(
-            (if cfg!(feature = "testnet") {
-                "testnet"
-            } else {
-                "near"
-            })
-            .to_string(),
-        )
+        self.take_linkdrop(&public_key);
+        true
     }
 }
 
 // Private methods
 impl Contract {
-    pub(crate) fn send(&mut self, public_key: PublicKey, mint_for_free: bool) -> Promise {
-        self.add_key(public_key, mint_for_free)
-    }
-    fn create_account(&self, new_account_id: AccountId, new_public_key: PublicKey) -> Promise {
-        log!("creating account for {}", &new_account_id);
-        ext_linkdrop::create_account(
-            new_account_id,
-            new_public_key,
-            self.get_linkdrop_contract(),
-            LINKDROP_DEPOSIT,
-            ON_CREATE_ACCOUNT_GAS,
-        )
-    }
-
-    fn add_key(&mut self, key: PublicKey, mint_for_free: bool) -> Promise {
-        // insert returns false if key was present
-        if self.accounts.insert(&key, &mint_for_free).is_some() {
+    /// Reserve a token, paid for by `linkdrop.payer`, for the linkdrop of `public_key` and add the
+    /// key to the linkdrop proxy
+    pub(crate) fn reserve_linkdrop(
+        &mut self,
+        public_key: PublicKey,
+        linkdrop: Linkdrop,
+    ) -> Promise {
+        let linkdrop_proxy = self
+            .linkdrop_proxy
+            .clone()
+            .unwrap_or_else(|| env::panic_str("No linkdrop proxy"));
+        if self.accounts.contains_key(&public_key)
+            || self.linkdrops.insert(&public_key, &linkdrop).is_some()
+        {
             env::panic_str("key already added");
         }
-        Promise::new(env::current_account_id()).add_access_key(
-            key,
-            ACCESS_KEY_ALLOWANCE,
-            env::current_account_id(),
-            "claim,create_account_and_claim".to_string(),
+        self.pending_tokens += 1;
+        self.pending_linkdrops += 1;
+        log!("Reserved a token for {:?}", public_key);
+        ext_linkdrop_proxy::create_linkdrop(
+            public_key,
+            linkdrop_proxy,
+            LINKDROP_DEPOSIT,
+            GAS_FOR_PROXY_CREATE_LINKDROP,
         )
     }
 
-    pub(crate) fn delete_access_key(&mut self, public_key: PublicKey) -> (bool, Promise) {
-        let mint_for_free = self.accounts.remove(&public_key);
-        require!(mint_for_free.is_some(), "Can't use a full access key.");
-        (
-            mint_for_free.unwrap(),
-            Promise::new(env::current_account_id()).delete_key(public_key),
-        )
+    /// Remove the linkdrop of `public_key`, releasing its token
+    pub(crate) fn take_linkdrop(&mut self, public_key: &PublicKey) -> Option<Linkdrop> {
+        let linkdrop = self.linkdrops.remove(public_key)?;
+        self.pending_tokens -= 1;
+        self.pending_linkdrops -= 1;
+        Some(linkdrop)
     }
 
-    fn delete_current_access_key(&mut self) -> (bool, Promise) {
-        self.delete_access_key(env::signer_account_pk())
+    /// Remove the key of the linkdrop of `public_key` from the linkdrop proxy, releasing its token
+    /// once it has been removed, or from this contract for a linkdrop created before the proxy
+    pub(crate) fn release_linkdrop(&mut self, public_key: PublicKey) -> Promise {
+        if self.accounts.remove(&public_key).is_some() {
+            self.pending_tokens -= 1;
+            return Promise::new(env::current_account_id()).delete_key(public_key);
+        }
+        require!(
+            self.linkdrops.contains_key(&public_key),
+            "No linkdrop for key"
+        );
+        let linkdrop_proxy = self
+            .linkdrop_proxy
+            .clone()
+            .unwrap_or_else(|| env::panic_str("No linkdrop proxy"));
+        ext_linkdrop_proxy::delete_linkdrop(
+            public_key.clone(),
+            linkdrop_proxy,
+            0,
+            GAS_FOR_PROXY_DELETE_LINKDROP,
+        )
+        .then(ext_self::on_linkdrop_deleted(
+            public_key,
+            env::current_account_id(),
+            0,
+            GAS_FOR_ON_LINKDROP_DELETED,
+        ))
     }
 }
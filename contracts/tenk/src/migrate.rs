@@ -0,0 +1,274 @@
+//! The contract's state is a single Borsh struct, so changing the fields of `Contract`, or of any
+//! type stored in it like `Sale`, breaks deserializing the state of deployed contracts.
+//!
+//! When changing the layout, freeze a copy of the previous layout here, e.g. `ContractV2`, bump
+//! `STATE_VERSION` and add a conversion from the old layout to `migrate`.
+use crate::*;
+use events::log_privileged_call;
+use near_sdk::{json_types::Base58CryptoHash, serde_json::json, IntoStorageKey};
+use std::convert::TryInto;
+
+/// Version of the layout of `Contract`
+pub const STATE_VERSION: u32 = 2;
+
+/// Gas kept by the call deploying new code to finish, the rest is given to `migrate`
+const GAS_FOR_UPGRADE: Gas = Gas(parse_gas!("10 Tgas") as u64);
+
+/// Layout of the contract before its state was versioned
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV1 {
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+    raffle: Raffle,
+    pending_tokens: u32,
+    accounts: LookupMap<PublicKey, bool>,
+    whitelist: LookupMap<AccountId, Allowance>,
+    sale: SaleV1,
+    admins: UnorderedSet<AccountId>,
+    media_extension: Option<String>,
+}
+
+/// Layout of `Sale` in `ContractV1`
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SaleV1 {
+    royalties: Option<Royalties>,
+    initial_royalties: Option<Royalties>,
+    presale_start: Option<TimestampMs>,
+    public_sale_start: Option<TimestampMs>,
+    allowance: Option<u16>,
+    presale_price: Option<U128>,
+    price: U128,
+    mint_rate_limit: Option<u16>,
+}
+
+impl From<SaleV1> for Sale {
+    fn from(sale: SaleV1) -> Self {
+        Self {
+            royalties: sale.royalties,
+            initial_royalties: sale.initial_royalties,
+            presale_start: sale.presale_start,
+            public_sale_start: sale.public_sale_start,
+            allowance: sale.allowance,
+            max_per_account: None,
+            presale_price: sale.presale_price,
+            price: sale.price,
+            mint_rate_limit: sale.mint_rate_limit,
+            gift_allowance: None,
+            pass_price: None,
+            discounts: None,
+            bundles: None,
+        }
+    }
+}
+
+impl From<ContractV1> for Contract {
+    /// Fields added since `ContractV1` start empty, and there is no backup owner. Mints made
+    /// before the migration are counted through the whitelist, to which `ContractV1` added every
+    /// account that minted, see `minted_by`. They aren't counted in stats.
+    fn from(contract: ContractV1) -> Self {
+        Self {
+            tokens: contract.tokens,
+            metadata: contract.metadata,
+            raffle: contract.raffle,
+            pending_tokens: contract.pending_tokens,
+            accounts: contract.accounts,
+            whitelist: contract.whitelist,
+            sale: contract.sale.into(),
+            admins: contract.admins,
+            media_extension: contract.media_extension,
+            metadata_updaters: UnorderedSet::new(StorageKey::MetadataUpdaters),
+            series: UnorderedMap::new(StorageKey::Series),
+            token_royalties: LookupMap::new(StorageKey::TokenRoyalties),
+            royalty_ranges: Vector::new(StorageKey::RoyaltyRanges),
+            platform_fee: PlatformFee::default(),
+            proceeds: LookupMap::new(StorageKey::Proceeds),
+            escrow: Escrow::new(StorageKey::EscrowPayments, StorageKey::EscrowHeldBySeries),
+            sale_stats: LazyOption::new(StorageKey::SaleStats, None),
+            account_stats: LookupMap::new(StorageKey::AccountStats),
+            minted: LookupMap::new(StorageKey::Minted),
+            trusted_minters: UnorderedSet::new(StorageKey::TrustedMinters),
+            passes: LookupMap::new(StorageKey::Passes),
+            pass_contract: None,
+            referral_program: None,
+            referrals: LookupMap::new(StorageKey::Referrals),
+            roles: LookupMap::new(StorageKey::Roles),
+            pending_owner: None,
+            backup_owner: None,
+            paused: Vec::new(),
+            timelock: None,
+            pending_changes: Vec::new(),
+            next_change_id: 0,
+            dao: None,
+            linkdrop_proxy: None,
+            linkdrops: LookupMap::new(StorageKey::Linkdrops),
+            pending_dao: None,
+            pending_linkdrops: 0,
+            gift_approvals: LookupSet::new(StorageKey::GiftApprovals),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Version of the layout of the contract's state
+    pub fn contract_version(&self) -> u32 {
+        Self::state_version()
+    }
+
+    /// Migrate the state of an older version of the contract to the current layout. Called after
+    /// deploying new code, and does nothing if the state is already current.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let version = Self::state_version();
+        let contract = match version {
+            1 => env::state_read::<ContractV1>().map(Contract::from),
+            STATE_VERSION => env::state_read::<Contract>(),
+            _ => env::panic_str(&format!("Cannot migrate from version {}", version)),
+        }
+        .unwrap_or_else(|| env::panic_str("No state to migrate"));
+        log!("Migrated from version {} to {}", version, STATE_VERSION);
+        Self::write_state_version();
+        contract
+    }
+
+    /// Stage new code for this contract, then deploy it and call `migrate`. The code is the raw
+    /// input of the call rather than JSON arguments. If `migrate` fails the new code isn't
+    /// deployed. Subject to the `timelock`, if there is one, in which case the staged code is
+    /// deployed by executing the pending change. Staging other code replaces it.
+    /// @allow ["::owner"]
+    pub fn upgrade(&mut self) -> bool {
+        self.assert_owner();
+        let code = env::input().unwrap_or_else(|| env::panic_str("Missing code to deploy"));
+        let code_hash = code_hash(&code);
+        log_privileged_call("upgrade", json!({ "code_hash": code_hash }));
+        env::storage_write(&StorageKey::StagedCode.into_storage_key(), &code);
+        self.apply_or_queue(Change::Upgrade(code_hash));
+        true
+    }
+}
+
+impl Contract {
+    /// Deploy the staged code, if it has the given hash, and call `migrate`
+    pub(crate) fn deploy_staged(&mut self, code_hash: String) {
+        let key = StorageKey::StagedCode.into_storage_key();
+        let code = env::storage_read(&key).unwrap_or_else(|| env::panic_str("No staged code"));
+        require!(
+            self::code_hash(&code) == code_hash,
+            "Staged code has been replaced"
+        );
+        env::storage_remove(&key);
+        require!(
+            env::prepaid_gas() > env::used_gas() + GAS_FOR_UPGRADE,
+            "Not enough gas to upgrade"
+        );
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                "migrate".to_string(),
+                vec![],
+                0,
+                env::prepaid_gas() - env::used_gas() - GAS_FOR_UPGRADE,
+            );
+    }
+
+    /// Contracts deployed before the state was versioned have no version stored
+    fn state_version() -> u32 {
+        env::storage_read(&StorageKey::StateVersion.into_storage_key()).map_or(1, |bytes| {
+            u32::try_from_slice(&bytes).unwrap_or_else(|_| env::abort())
+        })
+    }
+
+    pub(crate) fn write_state_version() {
+        env::storage_write(
+            &StorageKey::StateVersion.into_storage_key(),
+            &STATE_VERSION.to_le_bytes(),
+        );
+    }
+}
+
+/// Base58 hash of code to deploy
+fn code_hash(code: &[u8]) -> String {
+    let code_hash: [u8; 32] = env::sha256(code).try_into().unwrap();
+    String::from(&Base58CryptoHash::from(code_hash))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils::VMContextBuilder, testing_env};
+
+    /// State of a contract deployed before the state was versioned, owned by `owner.near` with
+    /// 98 tokens left to raffle, one of them pending, a price of 1N and a presale price of 0.5N
+    const CONTRACT_V1: &[u8] = include_bytes!("../fixtures/contract_v1.borsh");
+
+    fn owner() -> AccountId {
+        AccountId::new_unchecked("owner.near".to_string())
+    }
+
+    #[test]
+    fn migrate_from_v1() {
+        testing_env!(VMContextBuilder::new().build());
+        env::storage_write(b"STATE", CONTRACT_V1);
+        let contract = Contract::migrate();
+        assert_eq!(contract.contract_version(), STATE_VERSION);
+        assert_eq!(contract.owner(), owner());
+        assert_eq!(contract.tokens_left(), 97);
+        assert_eq!(contract.admins.len(), 1);
+        assert_eq!(contract.media_extension.as_deref(), Some("png"));
+        assert_eq!(contract.sale.price.0, parse_near!("1 N"));
+        assert_eq!(contract.sale.presale_price.unwrap().0, parse_near!("0.5 N"));
+        assert_eq!(contract.sale.allowance, Some(5));
+        assert_eq!(contract.sale.mint_rate_limit, Some(10));
+        assert_eq!(
+            contract.sale.royalties.as_ref().unwrap().accounts[&owner()],
+            10_000
+        );
+        assert!(contract.sale.discounts.is_none());
+        assert!(contract.backup_owner().is_none());
+    }
+
+    #[test]
+    fn v1_mints_count_towards_allowance() {
+        testing_env!(VMContextBuilder::new().build());
+        env::storage_write(b"STATE", CONTRACT_V1);
+        let mut contract = Contract::migrate();
+        let account_id = AccountId::new_unchecked("alice.near".to_string());
+        let mut allowance = Allowance::new(5);
+        allowance.use_num(2);
+        contract.whitelist.insert(&account_id, &allowance);
+        assert_eq!(contract.minted_by_account(&account_id), 2);
+    }
+
+    #[test]
+    fn migrate_current_version() {
+        testing_env!(VMContextBuilder::new().build());
+        let contract = Contract::new_default_meta(
+            owner(),
+            InitialMetadata {
+                name: "name".to_string(),
+                symbol: "sym".to_string(),
+                uri: "https://".to_string(),
+                ..Default::default()
+            },
+            100,
+            None,
+            None,
+            None,
+            None,
+        );
+        env::state_write(&contract);
+        let contract = Contract::migrate();
+        assert_eq!(contract.contract_version(), STATE_VERSION);
+        assert_eq!(contract.tokens_left(), 100);
+        assert!(contract.backup_owner().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "No state to migrate")]
+    fn migrate_without_state() {
+        testing_env!(VMContextBuilder::new().build());
+        Contract::migrate();
+    }
+}
@@ -1,33 +1,86 @@
 use crate::*;
+use events::log_privileged_call;
+use near_sdk::serde_json::json;
 
 #[near_bindgen]
 impl Contract {
     // Owner private methods
 
-    /// @allow ["::admins", "::owner"]
+    /// Propose a new owner, who becomes the owner once they call `accept_ownership`.
+    /// Replaces any earlier proposal.
+    /// @allow ["::owner"]
     pub fn transfer_ownership(&mut self, new_owner: AccountId) -> bool {
         self.assert_owner();
+        log_privileged_call("transfer_ownership", json!({ "new_owner": new_owner }));
+        env::log_str(&format!(
+            "{} proposes to transfer ownership to {}",
+            self.tokens.owner_id, new_owner
+        ));
+        self.pending_owner = Some(new_owner);
+        true
+    }
+
+    /// Cancel a proposed ownership transfer
+    /// @allow ["::owner"]
+    pub fn cancel_ownership_transfer(&mut self) -> bool {
+        self.assert_owner();
+        log_privileged_call("cancel_ownership_transfer", json!({}));
+        self.pending_owner.take().is_some()
+    }
+
+    /// Become the owner. Can only be called by the account proposed with `transfer_ownership`
+    pub fn accept_ownership(&mut self) -> bool {
+        let new_owner = env::predecessor_account_id();
+        require!(
+            self.pending_owner.as_ref() == Some(&new_owner),
+            "Method is private to the proposed owner"
+        );
+        log_privileged_call("accept_ownership", json!({}));
         env::log_str(&format!(
             "{} transfers ownership to {}",
             self.tokens.owner_id, new_owner
         ));
+        self.pending_owner = None;
         self.tokens.owner_id = new_owner;
         true
     }
 
+    /// Set or remove the backup owner, who has the same permissions as the owner.
+    /// Can only be called by the owner, not the backup owner.
+    /// @allow ["::owner"]
+    pub fn update_backup_owner(&mut self, backup_owner: Option<AccountId>) -> bool {
+        require!(
+            &env::predecessor_account_id() == self.dao.as_ref().unwrap_or(&self.tokens.owner_id),
+            "Method is private to owner"
+        );
+        log_privileged_call(
+            "update_backup_owner",
+            json!({ "backup_owner": backup_owner }),
+        );
+        self.backup_owner = backup_owner;
+        true
+    }
+
+    /// Subject to the `timelock`, if there is one.
     /// @allow ["::admins", "::owner"]
     pub fn update_initial_royalties(&mut self, initial_royalties: Royalties) -> bool {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::RoyaltyManager);
+        log_privileged_call(
+            "update_initial_royalties",
+            json!({ "initial_royalties": initial_royalties }),
+        );
         initial_royalties.validate();
-        self.sale.initial_royalties = Some(initial_royalties);
+        self.apply_or_queue(Change::InitialRoyalties(initial_royalties));
         true
     }
 
+    /// Subject to the `timelock`, if there is one.
     /// @allow ["::admins", "::owner"]
     pub fn update_royalties(&mut self, royalties: Royalties) -> bool {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::RoyaltyManager);
+        log_privileged_call("update_royalties", json!({ "royalties": royalties }));
         royalties.validate();
-        self.sale.royalties = Some(royalties);
+        self.apply_or_queue(Change::Royalties(royalties));
         true
     }
 
@@ -36,18 +89,70 @@ impl Contract {
     /// e.g. submit with no `allowance` argument
     /// @allow ["::admins", "::owner"]
     pub fn update_allowance(&mut self, allowance: Option<u16>) -> bool {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("update_allowance", json!({ "allowance": allowance }));
         self.sale.allowance = allowance;
         true
     }
 
+    /// Maximum number of tokens an account can mint across the presale and public sale.
+    /// When it isn't provided, there is no lifetime limit.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_max_per_account(&mut self, max_per_account: Option<u16>) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_max_per_account",
+            json!({ "max_per_account": max_per_account }),
+        );
+        self.sale.max_per_account = max_per_account;
+        true
+    }
+
+    /// Discounts for minting at least a given number of tokens at once.
+    /// Passing no `discounts` removes them.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_discounts(&mut self, discounts: Option<Vec<QuantityDiscount>>) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("update_discounts", json!({ "discounts": discounts }));
+        let mut sale = self.sale.clone();
+        sale.discounts = discounts.clone();
+        sale.validate();
+        self.apply_or_queue(Change::Discounts(discounts));
+        true
+    }
+
+    /// Fixed prices for minting an exact number of tokens at once.
+    /// Passing no `bundles` removes them.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_bundles(&mut self, bundles: Option<Vec<Bundle>>) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("update_bundles", json!({ "bundles": bundles }));
+        let mut sale = self.sale.clone();
+        sale.bundles = bundles.clone();
+        sale.validate();
+        self.apply_or_queue(Change::Bundles(bundles));
+        true
+    }
+
+    /// Whose allowance is used when minting to another account with `nft_mint_to`.
+    /// When it isn't provided, the account paying uses its allowance.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_gift_allowance(&mut self, gift_allowance: Option<GiftAllowance>) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_gift_allowance",
+            json!({ "gift_allowance": gift_allowance }),
+        );
+        self.sale.gift_allowance = gift_allowance;
+        true
+    }
+
+    /// Update the base URI. Subject to the `timelock`, if there is one.
     /// @allow ["::admins", "::owner"]
     pub fn update_uri(&mut self, uri: String) -> bool {
-        self.assert_owner_or_admin();
-        let mut metadata = self.metadata.get().unwrap();
-        log!("New URI: {}", &uri);
-        metadata.base_uri = Some(uri);
-        self.metadata.set(&metadata);
+        self.assert_role(Role::MetadataManager);
+        log_privileged_call("update_uri", json!({ "uri": uri }));
+        self.apply_or_queue(Change::Uri(uri));
         true
     }
 
@@ -58,8 +163,11 @@ impl Contract {
         accounts: Vec<AccountId>,
         max_allowance: Option<u16>,
     ) -> bool {
-        #[cfg(feature = "testnet")]
-        self.assert_owner_or_admin();
+        self.assert_role(Role::WhitelistManager);
+        log_privileged_call(
+            "add_whitelist_accounts",
+            json!({ "accounts": accounts, "max_allowance": max_allowance }),
+        );
         let max_allowance = max_allowance.unwrap_or_else(|| self.sale.allowance.unwrap_or(0));
         accounts.iter().for_each(|account_id| {
             let allowance = self
@@ -75,7 +183,11 @@ impl Contract {
     /// Remove whitelisted account. If account is removed, the number of tokens left in returned.
     /// @allow ["::admins", "::owner"]
     pub fn remove_whitelist_account(&mut self, account_id: AccountId) -> Option<u16> {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::WhitelistManager);
+        log_privileged_call(
+            "remove_whitelist_account",
+            json!({ "account_id": account_id }),
+        );
         self.whitelist.remove(&account_id).as_ref().map(Allowance::left)
     }
 
@@ -87,7 +199,11 @@ impl Contract {
         accounts: Vec<AccountId>,
         allowance_increase: u16,
     ) -> bool {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::WhitelistManager);
+        log_privileged_call(
+            "update_whitelist_accounts",
+            json!({ "accounts": accounts, "allowance_increase": allowance_increase }),
+        );
         accounts.iter().for_each(|account_id| {
             if let Some(mut allowance) = self.whitelist.get(account_id) {
                 allowance.increase_max(allowance_increase);
@@ -102,8 +218,8 @@ impl Contract {
     /// End public sale/minting, going back to the pre-presale state in which no one can mint.
     /// @allow ["::admins", "::owner"]
     pub fn close_sale(&mut self) -> bool {
-        #[cfg(not(feature = "testnet"))]
-        self.assert_owner_or_admin();
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("close_sale", json!({}));
         self.sale.presale_start = None;
         self.sale.public_sale_start = None;
         true
@@ -120,42 +236,91 @@ impl Contract {
         public_sale_start: Option<TimestampMs>,
         presale_price: Option<U128>,
     ) -> bool {
-        #[cfg(not(feature = "testnet"))]
-        self.assert_owner_or_admin();
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "start_presale",
+            json!({ "public_sale_start": public_sale_start, "presale_price": presale_price }),
+        );
         let current_time = current_time_ms();
         self.sale.presale_start = Some(current_time);
         self.sale.public_sale_start = public_sale_start;
         if presale_price.is_some() {
-            self.sale.presale_price = presale_price;
+            self.apply_or_queue(Change::PresalePrice(presale_price));
         }
         true
     }
 
     /// @allow ["::admins", "::owner"]
     pub fn start_sale(&mut self, price: Option<YoctoNEAR>) -> bool {
-        #[cfg(not(feature = "testnet"))]
-        self.assert_owner_or_admin();
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("start_sale", json!({ "price": price }));
         self.sale.public_sale_start = Some(current_time_ms());
         if let Some(price) = price {
-            self.sale.price = price
+            self.apply_or_queue(Change::Price(price));
         }
         true
     }
 
     /// Add a new admin. Careful who you add!
-    /// @allow ["::admins", "::owner"]
+    /// @allow ["::owner"]
     pub fn add_admin(&mut self, account_id: AccountId) -> bool {
-        self.assert_owner_or_admin();
+        self.assert_owner();
+        log_privileged_call("add_admin", json!({ "account_id": account_id }));
         self.admins.insert(&account_id);
         true
     }
 
+    /// Remove an admin. Returns whether the account was an admin
+    /// @allow ["::owner"]
+    pub fn remove_admin(&mut self, account_id: AccountId) -> bool {
+        self.assert_owner();
+        log_privileged_call("remove_admin", json!({ "account_id": account_id }));
+        self.admins.remove(&account_id)
+    }
+
+    /// Allow an account, e.g. a game contract, to update the mutable metadata of any token
+    /// @allow ["::admins", "::owner"]
+    pub fn add_metadata_updater(&mut self, account_id: AccountId) -> bool {
+        self.assert_role(Role::MetadataManager);
+        log_privileged_call("add_metadata_updater", json!({ "account_id": account_id }));
+        self.metadata_updaters.insert(&account_id)
+    }
+
+    /// Remove an account's ability to update token metadata
+    /// @allow ["::admins", "::owner"]
+    pub fn remove_metadata_updater(&mut self, account_id: AccountId) -> bool {
+        self.assert_role(Role::MetadataManager);
+        log_privileged_call(
+            "remove_metadata_updater",
+            json!({ "account_id": account_id }),
+        );
+        self.metadata_updaters.remove(&account_id)
+    }
+
+    /// Allow a contract, e.g. a sales contract, to mint on behalf of users with `nft_mint_on_behalf`
+    /// @allow ["::admins", "::owner"]
+    pub fn add_trusted_minter(&mut self, account_id: AccountId) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("add_trusted_minter", json!({ "account_id": account_id }));
+        self.trusted_minters.insert(&account_id)
+    }
+
+    /// Remove a contract's ability to mint on behalf of users
+    /// @allow ["::admins", "::owner"]
+    pub fn remove_trusted_minter(&mut self, account_id: AccountId) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("remove_trusted_minter", json!({ "account_id": account_id }));
+        self.trusted_minters.remove(&account_id)
+    }
+
     /// Update public sale price.
     /// Careful this is in yoctoNear: 1N = 1000000000000000000000000 yN
+    /// Subject to the `timelock`, if there is one.
     /// @allow ["::admins", "::owner"]
     pub fn update_price(&mut self, price: U128) -> bool {
-        self.assert_owner_or_admin();
-        self.sale.price = price;
+        self.assert_role(Role::SaleManager);
+        log_privileged_call("update_price", json!({ "price": price }));
+        self.apply_or_queue(Change::Price(price));
         true
     }
 
@@ -163,8 +328,12 @@ impl Contract {
     /// Careful this is in yoctoNear: 1N = 1000000000000000000000000 yN
     /// @allow ["::admins", "::owner"]
     pub fn update_presale_price(&mut self, presale_price: Option<U128>) -> bool {
-        self.assert_owner_or_admin();
-        self.sale.presale_price = presale_price;
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_presale_price",
+            json!({ "presale_price": presale_price }),
+        );
+        self.apply_or_queue(Change::PresalePrice(presale_price));
         true
     }
 
@@ -172,7 +341,11 @@ impl Contract {
     /// Careful this is in ms since 1970
     /// @allow ["::admins", "::owner"]
     pub fn update_presale_start(&mut self, presale_start: TimestampMs) -> bool {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_presale_start",
+            json!({ "presale_start": presale_start }),
+        );
         self.sale.presale_start = Some(presale_start);
         true
     }
@@ -181,40 +354,191 @@ impl Contract {
     /// Careful this is in ms since 1970
     /// @allow ["::admins", "::owner"]
     pub fn update_public_sale_start(&mut self, public_sale_start: TimestampMs) -> bool {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_public_sale_start",
+            json!({ "public_sale_start": public_sale_start }),
+        );
         self.sale.public_sale_start = Some(public_sale_start);
         true
     }
 
     #[payable]
-    /// Create a pending token that can be claimed with corresponding private key
+    /// Create a pending token that can be claimed with corresponding private key,
+    /// through the linkdrop proxy
     /// @allow ["::admins", "::owner"]
     pub fn create_linkdrop(&mut self, public_key: PublicKey) -> Promise {
-        self.assert_owner_or_admin();
+        self.assert_role(Role::LinkdropIssuer);
+        self.assert_not_paused(PauseScope::Linkdrop);
+        log_privileged_call("create_linkdrop", json!({ "public_key": public_key }));
         let deposit = env::attached_deposit();
         let account = &env::predecessor_account_id();
-        self.assert_can_mint(account, 1);
+        self.assert_can_mint(account, account, 1);
         let total_cost = self.cost_of_linkdrop(account).0;
-        self.pending_tokens += 1;
-        let mint_for_free = self.is_owner(account);
-        self.use_whitelist_allowance(account, 1);
+        let linkdrop = Linkdrop {
+            payer: account.clone(),
+            price: self.price_of(1, account),
+            status: self.get_status(),
+        };
+        self.use_allowance(account, 1);
         log!("Total cost of creation is {}", total_cost);
         refund(account, deposit - total_cost);
-        self.send(public_key, mint_for_free)
+        self.reserve_linkdrop(public_key.clone(), linkdrop)
             .then(ext_self::on_send_with_callback(
+                account.clone(),
+                public_key,
                 env::current_account_id(),
-                total_cost,
+                total_cost - LINKDROP_DEPOSIT,
                 GAS_REQUIRED_TO_CREATE_LINKDROP,
             ))
     }
 
     #[payable]
-    /// Delete an linkdrop and decrease the number of pending tokens.
+    /// Delete an linkdrop and decrease the number of pending tokens once its key is removed.
     /// @allow ["::admins", "::owner"]
     pub fn delete_linkdrop(&mut self, public_key: PublicKey) -> Promise {
-        self.assert_owner_or_admin();
-        let promise = self.delete_access_key(public_key).1;
-        self.pending_tokens -= 1;
-        promise
+        self.assert_role(Role::LinkdropIssuer);
+        log_privileged_call("delete_linkdrop", json!({ "public_key": public_key }));
+        self.release_linkdrop(public_key)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils::VMContextBuilder, testing_env};
+    use std::collections::HashMap;
+
+    fn owner() -> AccountId {
+        AccountId::new_unchecked("owner.near".to_string())
+    }
+
+    fn stranger() -> AccountId {
+        AccountId::new_unchecked("stranger.near".to_string())
+    }
+
+    fn public_key() -> PublicKey {
+        "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp"
+            .parse()
+            .unwrap()
+    }
+
+    fn royalties() -> Royalties {
+        Royalties {
+            accounts: HashMap::from([(stranger(), 10_000)]),
+            percent: 1_000,
+        }
+    }
+
+    /// Contract owned by `owner()` with the following calls made by `stranger()`
+    fn called_by_stranger() -> Contract {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .build());
+        let metadata = NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "name".to_string(),
+            symbol: "sym".to_string(),
+            icon: None,
+            base_uri: Some("https://".to_string()),
+            reference: None,
+            reference_hash: None,
+        };
+        let mut contract = Contract::new(
+            owner(),
+            metadata,
+            100,
+            Sale::default(),
+            None,
+            None,
+            Some(owner()),
+        );
+        contract.create_series(
+            "series".to_string(),
+            InitialSeries {
+                title: "series".to_string(),
+                uri: "https://".to_string(),
+                size: 10,
+                sale: None,
+            },
+        );
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(stranger())
+            .signer_account_id(stranger())
+            .attached_deposit(parse_near!("1 N"))
+            .build());
+        contract
+    }
+
+    /// Each privileged method must panic when called by an account without permission
+    macro_rules! stranger_cannot_call {
+        ($($name:ident: |$contract:ident| $call:expr;)*) => {
+            $(
+                #[test]
+                #[should_panic(expected = "Method")]
+                fn $name() {
+                    let mut $contract = called_by_stranger();
+                    $call;
+                }
+            )*
+        };
+    }
+
+    stranger_cannot_call! {
+        transfer_ownership: |c| c.transfer_ownership(stranger());
+        cancel_ownership_transfer: |c| c.cancel_ownership_transfer();
+        accept_ownership: |c| c.accept_ownership();
+        update_backup_owner: |c| c.update_backup_owner(Some(stranger()));
+        pause: |c| c.pause(None);
+        unpause: |c| c.unpause(None);
+        update_timelock: |c| c.update_timelock(None);
+        update_dao: |c| c.update_dao(Some(stranger()));
+        update_linkdrop_proxy: |c| c.update_linkdrop_proxy(Some(stranger()));
+        upgrade: |c| c.upgrade();
+        update_initial_royalties: |c| c.update_initial_royalties(royalties());
+        update_royalties: |c| c.update_royalties(royalties());
+        update_allowance: |c| c.update_allowance(Some(100));
+        update_max_per_account: |c| c.update_max_per_account(Some(100));
+        update_discounts: |c| c.update_discounts(None);
+        update_bundles: |c| c.update_bundles(None);
+        update_gift_allowance: |c| c.update_gift_allowance(Some(GiftAllowance::Receiver));
+        update_uri: |c| c.update_uri("https://".to_string());
+        add_whitelist_accounts: |c| c.add_whitelist_accounts(vec![stranger()], Some(100));
+        remove_whitelist_account: |c| c.remove_whitelist_account(stranger());
+        update_whitelist_accounts: |c| c.update_whitelist_accounts(vec![stranger()], 100);
+        close_sale: |c| c.close_sale();
+        start_presale: |c| c.start_presale(None, None);
+        start_sale: |c| c.start_sale(Some(U128(0)));
+        add_admin: |c| c.add_admin(stranger());
+        remove_admin: |c| c.remove_admin(stranger());
+        add_metadata_updater: |c| c.add_metadata_updater(stranger());
+        remove_metadata_updater: |c| c.remove_metadata_updater(stranger());
+        add_trusted_minter: |c| c.add_trusted_minter(stranger());
+        remove_trusted_minter: |c| c.remove_trusted_minter(stranger());
+        update_price: |c| c.update_price(U128(0));
+        update_presale_price: |c| c.update_presale_price(Some(U128(0)));
+        update_presale_start: |c| c.update_presale_start(0);
+        update_public_sale_start: |c| c.update_public_sale_start(0);
+        create_linkdrop: |c| c.create_linkdrop(public_key());
+        delete_linkdrop: |c| c.delete_linkdrop(public_key());
+        grant_role: |c| c.grant_role(stranger(), Role::SaleManager);
+        revoke_role: |c| c.revoke_role(stranger(), Role::SaleManager);
+        update_funding_goal: |c| c.update_funding_goal(None);
+        issue_passes: |c| c.issue_passes(stranger(), 1);
+        update_pass_contract: |c| c.update_pass_contract(Some(stranger()));
+        set_token_royalties: |c| c.set_token_royalties(vec!["0".to_string()], Some(royalties()));
+        add_royalty_range: |c| c.add_royalty_range(0, 10, royalties());
+        remove_royalty_range: |c| c.remove_royalty_range(0);
+        update_referral_program: |c| c.update_referral_program(None);
+        create_series: |c| c.create_series("other".to_string(), InitialSeries {
+            title: "other".to_string(),
+            uri: "https://".to_string(),
+            size: 10,
+            sale: None,
+        });
+        update_series_sale: |c| c.update_series_sale("series".to_string(), Sale::default());
+        update_series_uri: |c| c.update_series_uri("series".to_string(), "https://".to_string());
+        update_platform_fee: |c| c.update_platform_fee(PlatformFee::default());
     }
 }
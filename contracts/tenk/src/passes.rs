@@ -0,0 +1,131 @@
+use crate::*;
+use events::log_privileged_call;
+use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+use near_sdk::serde_json::json;
+
+#[near_bindgen]
+impl Contract {
+    /// Number of passes an account holds, each redeemable for one token
+    pub fn passes_of(&self, account_id: AccountId) -> u32 {
+        self.passes.get(&account_id).unwrap_or(0)
+    }
+
+    /// NFT contract whose tokens can be sent to this contract in exchange for passes
+    pub fn pass_contract(&self) -> Option<AccountId> {
+        self.pass_contract.clone()
+    }
+
+    /// Issue `num` passes to an account, reserving a token from the raffle for each one.
+    /// @allow ["::admins", "::owner"]
+    pub fn issue_passes(&mut self, account_id: AccountId, num: u32) -> u32 {
+        self.assert_role(Role::LinkdropIssuer);
+        log_privileged_call(
+            "issue_passes",
+            json!({ "account_id": account_id, "num": num }),
+        );
+        self.reserve_passes(&account_id, num);
+        self.passes_of(account_id)
+    }
+
+    /// Set the NFT contract whose tokens are exchanged for passes when sent to this contract
+    /// with `nft_transfer_call`. Passing no `pass_contract` stops accepting them.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_pass_contract(&mut self, pass_contract: Option<AccountId>) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_pass_contract",
+            json!({ "pass_contract": pass_contract }),
+        );
+        self.pass_contract = pass_contract;
+        true
+    }
+
+    /// Transfer `num` of the caller's passes to another account.
+    /// Attached deposit must cover the storage of the receiver's passes, the rest is refunded.
+    #[payable]
+    pub fn transfer_passes(&mut self, receiver_id: AccountId, num: u32) -> u32 {
+        let sender_id = env::predecessor_account_id();
+        require!(num > 0, "Must transfer at least one pass");
+        require!(
+            sender_id != receiver_id,
+            "Cannot transfer passes to yourself"
+        );
+        let initial_storage_usage = env::storage_usage();
+        self.use_passes(&sender_id, num);
+        let received = self.passes_of(receiver_id.clone()) + num;
+        self.passes.insert(&receiver_id, &received);
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        self.charge_storage(&sender_id, storage_used);
+        self.passes_of(sender_id)
+    }
+
+    /// Redeem `num` of the caller's passes, minting a token for each at the sale's `pass_price`.
+    /// Attached deposit must cover the price and storage of the tokens, the rest is refunded.
+    #[payable]
+    pub fn redeem_passes(&mut self, num: u16) -> Vec<Token> {
+        self.assert_not_paused(PauseScope::Mint);
+        self.assert_funding_not_failed();
+        let account_id = &env::predecessor_account_id();
+        require!(num > 0, "Must redeem at least one pass");
+        self.use_passes(account_id, num as u32);
+        let status = self.get_status();
+        let price = num as Balance * self.sale.pass_price.map_or(0, |price| price.0);
+        let initial_storage_usage = env::storage_usage();
+        self.pending_tokens -= num as u32;
+        let tokens = self.nft_mint_many_ungaurded(num, account_id);
+        self.record_sale(account_id, num, price, &status);
+        if price > 0 {
+            self.receive_proceeds(&tokens, price, None);
+        }
+        let storage_used = env::storage_usage() - initial_storage_usage;
+        self.pay_for_mint(account_id, price, storage_used);
+        tokens
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenReceiver for Contract {
+    /// Exchange a token of the pass contract for a pass, which is given to its previous owner.
+    /// The token is kept by this contract, or returned if there are no tokens left to reserve.
+    #[allow(unused_variables)]
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        require!(
+            self.pass_contract.as_ref() == Some(&env::predecessor_account_id()),
+            "Only tokens of the pass contract are accepted"
+        );
+        if self.tokens_left() == 0 {
+            log!("No tokens left to reserve, returning {}", token_id);
+            return PromiseOrValue::Value(true);
+        }
+        self.reserve_passes(&previous_owner_id, 1);
+        PromiseOrValue::Value(false)
+    }
+}
+
+impl Contract {
+    fn reserve_passes(&mut self, account_id: &AccountId, num: u32) {
+        require!(
+            self.tokens_left() >= num,
+            "Not enough tokens left to reserve"
+        );
+        self.pending_tokens += num;
+        let passes = self.passes_of(account_id.clone()) + num;
+        self.passes.insert(account_id, &passes);
+    }
+
+    fn use_passes(&mut self, account_id: &AccountId, num: u32) {
+        let passes = self.passes_of(account_id.clone());
+        require!(passes >= num, "Not enough passes");
+        if passes == num {
+            self.passes.remove(account_id);
+        } else {
+            self.passes.insert(account_id, &(passes - num));
+        }
+    }
+}
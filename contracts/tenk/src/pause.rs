@@ -0,0 +1,72 @@
+use crate::*;
+use events::log_privileged_call;
+use near_sdk::serde_json::json;
+
+/// Part of the contract which can be paused in an emergency
+#[derive(
+    BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub enum PauseScope {
+    /// Minting, including series and passes
+    Mint,
+    /// `nft_transfer`, `nft_transfer_call` and `nft_transfer_payout`
+    Transfer,
+    /// Creating and claiming linkdrops
+    Linkdrop,
+    /// `nft_approve`
+    Approval,
+}
+
+impl PauseScope {
+    const ALL: [PauseScope; 4] = [
+        PauseScope::Mint,
+        PauseScope::Transfer,
+        PauseScope::Linkdrop,
+        PauseScope::Approval,
+    ];
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Parts of the contract which are currently paused
+    pub fn paused(&self) -> Vec<PauseScope> {
+        self.paused.clone()
+    }
+
+    /// Pause parts of the contract. Passing no `scopes` pauses everything
+    /// @allow ["::admins", "::owner"]
+    pub fn pause(&mut self, scopes: Option<Vec<PauseScope>>) -> bool {
+        self.assert_role(Role::Pauser);
+        let scopes = scopes.unwrap_or_else(|| PauseScope::ALL.to_vec());
+        log_privileged_call("pause", json!({ "scopes": scopes }));
+        scopes.into_iter().for_each(|scope| {
+            if !self.paused.contains(&scope) {
+                self.paused.push(scope);
+            }
+        });
+        true
+    }
+
+    /// Unpause parts of the contract. Passing no `scopes` unpauses everything
+    /// @allow ["::admins", "::owner"]
+    pub fn unpause(&mut self, scopes: Option<Vec<PauseScope>>) -> bool {
+        self.assert_role(Role::Pauser);
+        log_privileged_call("unpause", json!({ "scopes": scopes }));
+        match scopes {
+            Some(scopes) => self.paused.retain(|scope| !scopes.contains(scope)),
+            None => self.paused.clear(),
+        }
+        true
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_not_paused(&self, scope: PauseScope) {
+        require!(
+            !self.paused.contains(&scope),
+            format!("{:?} is paused", scope)
+        );
+    }
+}
@@ -1,10 +1,12 @@
 use crate::*;
+use events::log_privileged_call;
 use near_sdk::{
     assert_one_yocto,
     borsh::{self, BorshDeserialize, BorshSerialize},
     json_types::U128,
     near_bindgen,
     serde::{Deserialize, Serialize},
+    serde_json::json,
     AccountId, Promise,
 };
 
@@ -17,16 +19,35 @@ use std::collections::HashMap;
 /// maximum length specified by the financial contract obtaining this
 /// payout data. Any mapping of length 10 or less MUST be accepted by
 /// financial contracts, so 10 is a safe upper limit.
-
-/// This currently deviates from the standard but is in the process of updating to use this type
 #[derive(Default, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
 #[near_sdk::witgen]
 pub struct Payout {
-    payout: HashMap<AccountId, U128>,
+    pub payout: HashMap<AccountId, U128>,
 }
 
 impl Payout {
+    /// The whole balance goes to the token's owner
+    pub fn to_owner(balance: Balance, owner_id: &AccountId) -> Self {
+        Self {
+            payout: HashMap::from([(owner_id.clone(), balance.into())]),
+        }
+    }
+
+    /// Panics if there are more than `max_len_payout` accounts to pay
+    pub fn assert_max_len(&self, max_len_payout: Option<u32>) {
+        if let Some(max_len_payout) = max_len_payout {
+            require!(
+                self.payout.len() <= max_len_payout as usize,
+                format!(
+                    "payout has {} accounts, which exceeds max_len_payout of {}",
+                    self.payout.len(),
+                    max_len_payout
+                )
+            );
+        }
+    }
+
     pub fn send_funds(self) {
         self.payout.into_iter().for_each(|(account, amount)| {
             Promise::new(account).transfer(amount.0);
@@ -55,17 +76,18 @@ pub trait Payouts {
 
 #[near_bindgen]
 impl Payouts for Contract {
-    #[allow(unused_variables)]
     fn nft_payout(&self, token_id: String, balance: U128, max_len_payout: Option<u32>) -> Payout {
         let owner_id = self
             .tokens
             .owner_by_id
             .get(&token_id)
             .expect("No such token_id");
-        self.sale
-            .royalties
-            .as_ref()
-            .map_or(Payout::default(), |r| r.create_payout(balance.0, &owner_id))
+        let payout = self.royalties_for_token(&token_id).map_or_else(
+            || Payout::to_owner(balance.0, &owner_id),
+            |r| r.create_payout(balance.0, &owner_id, &self.platform_fee),
+        );
+        payout.assert_max_len(max_len_payout);
+        payout
     }
 
     #[payable]
@@ -85,13 +107,161 @@ impl Payouts for Contract {
     }
 }
 
+/// Maximum number of royalty ranges, since each is checked when finding a token's royalties
+const MAX_ROYALTY_RANGES: u64 = 20;
+
+/// Royalties for an inclusive range of token ids of the main collection
+#[near_sdk::witgen]
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltyRange {
+    pub start: u32,
+    pub end: u32,
+    pub royalties: Royalties,
+}
+
+impl RoyaltyRange {
+    fn contains(&self, id: u32) -> bool {
+        self.start <= id && id <= self.end
+    }
+
+    fn overlaps(&self, other: &RoyaltyRange) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Royalties paid when a token is sold. `None` means no royalties
+    pub fn royalties_of(&self, token_id: TokenId) -> Option<Royalties> {
+        self.royalties_for_token(&token_id)
+    }
+
+    /// Ranges of token ids with their own royalties
+    pub fn royalty_ranges(&self) -> Vec<RoyaltyRange> {
+        self.royalty_ranges.to_vec()
+    }
+
+    /// Set royalties for specific tokens, overriding the collection's royalties.
+    /// Passing no `royalties` removes the override.
+    /// @allow ["::admins", "::owner"]
+    pub fn set_token_royalties(
+        &mut self,
+        token_ids: Vec<TokenId>,
+        royalties: Option<Royalties>,
+    ) -> bool {
+        self.assert_role(Role::RoyaltyManager);
+        log_privileged_call(
+            "set_token_royalties",
+            json!({ "token_ids": token_ids, "royalties": royalties }),
+        );
+        if let Some(royalties) = royalties.as_ref() {
+            royalties.validate();
+        }
+        self.apply_or_queue(Change::TokenRoyalties {
+            token_ids,
+            royalties,
+        });
+        true
+    }
+
+    /// Set royalties for an inclusive range of token ids, overriding the collection's royalties.
+    /// Ranges cannot overlap.
+    /// @allow ["::admins", "::owner"]
+    pub fn add_royalty_range(&mut self, start: u32, end: u32, royalties: Royalties) -> bool {
+        self.assert_role(Role::RoyaltyManager);
+        log_privileged_call(
+            "add_royalty_range",
+            json!({ "start": start, "end": end, "royalties": royalties }),
+        );
+        require!(start <= end, "start of range must not be after its end");
+        royalties.validate();
+        let range = RoyaltyRange {
+            start,
+            end,
+            royalties,
+        };
+        self.assert_can_add_royalty_range(&range);
+        self.apply_or_queue(Change::AddRoyaltyRange(range));
+        true
+    }
+
+    /// Remove the royalty range starting at `start`
+    /// @allow ["::admins", "::owner"]
+    pub fn remove_royalty_range(&mut self, start: u32) -> bool {
+        self.assert_role(Role::RoyaltyManager);
+        log_privileged_call("remove_royalty_range", json!({ "start": start }));
+        self.royalty_range_index(start);
+        self.apply_or_queue(Change::RemoveRoyaltyRange(start));
+        true
+    }
+}
+
+impl Contract {
+    /// Royalties paid when a token is sold, in order of precedence:
+    /// the token's own royalties, those of a range containing it, those of its series
+    /// and finally the collection's.
+    pub(crate) fn royalties_for_token(&self, token_id: &str) -> Option<Royalties> {
+        self.token_royalties
+            .get(&token_id.to_string())
+            .or_else(|| {
+                let id: u32 = token_id.parse().ok()?;
+                self.royalty_ranges
+                    .iter()
+                    .find(|r| r.contains(id))
+                    .map(|r| r.royalties)
+            })
+            .or_else(|| self.series_royalties(token_id))
+            .or_else(|| self.sale.royalties.clone())
+    }
+
+    /// Set or, when there are no `royalties`, remove the royalties of specific tokens
+    pub(crate) fn set_royalties_of(
+        &mut self,
+        token_ids: &[TokenId],
+        royalties: Option<&Royalties>,
+    ) {
+        token_ids.iter().for_each(|token_id| {
+            if let Some(royalties) = royalties {
+                self.token_royalties.insert(token_id, royalties);
+            } else {
+                self.token_royalties.remove(token_id);
+            }
+        });
+    }
+
+    pub(crate) fn insert_royalty_range(&mut self, range: RoyaltyRange) {
+        self.assert_can_add_royalty_range(&range);
+        self.royalty_ranges.push(&range);
+    }
+
+    /// Index of the royalty range starting at `start`
+    pub(crate) fn royalty_range_index(&self, start: u32) -> u64 {
+        self.royalty_ranges
+            .iter()
+            .position(|r| r.start == start)
+            .unwrap_or_else(|| env::panic_str("No royalty range starts at this id")) as u64
+    }
+
+    fn assert_can_add_royalty_range(&self, range: &RoyaltyRange) {
+        require!(
+            self.royalty_ranges.len() < MAX_ROYALTY_RANGES,
+            "too many royalty ranges"
+        );
+        require!(
+            !self.royalty_ranges.iter().any(|r| r.overlaps(range)),
+            "range overlaps an existing royalty range"
+        );
+    }
+}
+
 #[near_sdk::witgen]
-type BasisPoint = u16;
+pub type BasisPoint = u16;
 
-const ONE_HUNDRED_PERCENT_IN_BPS: BasisPoint = 10_000;
+pub(crate) const ONE_HUNDRED_PERCENT_IN_BPS: BasisPoint = 10_000;
 
 #[near_sdk::witgen]
-#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Default)]
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Default, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Royalties {
     pub accounts: HashMap<AccountId, BasisPoint>,
@@ -121,7 +291,12 @@ impl Royalties {
             "total percent of each royalty split must equal 10,000"
         )
     }
-    pub(crate) fn create_payout(&self, balance: Balance, owner_id: &AccountId) -> Payout {
+    pub(crate) fn create_payout(
+        &self,
+        balance: Balance,
+        owner_id: &AccountId,
+        fee: &PlatformFee,
+    ) -> Payout {
         let royalty_payment = apply_percent(self.percent, balance);
         let mut payout = Payout {
             payout: self
@@ -135,44 +310,65 @@ impl Royalties {
                 })
                 .collect(),
         }
-        .tenk_royalities();
+        .apply_platform_fee(fee);
         let rest = balance - u128::min(royalty_payment, balance);
         let owner_payout: u128 = payout.payout.get(owner_id).map_or(0, |x| x.0) + rest;
         payout.payout.insert(owner_id.clone(), owner_payout.into());
         payout
     }
-
-    pub(crate) fn send_funds(&self, balance: Balance, owner_id: &AccountId) {
-        self.create_payout(balance, owner_id).send_funds();
-    }
 }
 
-fn apply_percent(percent: BasisPoint, int: u128) -> u128 {
+pub(crate) fn apply_percent(percent: BasisPoint, int: u128) -> u128 {
     int * percent as u128 / 10_000u128
 }
 
 // Thanks for using our code. Here is a suggested donation.
 
+/// Fee taken by the platform from every royalty payment, paid to `account_id`
+#[near_sdk::witgen]
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlatformFee {
+    pub account_id: AccountId,
+    pub bps: BasisPoint,
+}
+
+impl Default for PlatformFee {
+    fn default() -> Self {
+        Self {
+            account_id: tenk_account(),
+            // Currently 4.8%
+            bps: 480,
+        }
+    }
+}
+
+impl PlatformFee {
+    pub(crate) fn validate(&self) {
+        require!(
+            self.bps <= ONE_HUNDRED_PERCENT_IN_BPS,
+            "platform fee is in basis points and must be between 0 - 10,000"
+        );
+    }
+}
+
 #[doc(hidden)]
 impl Payout {
-    pub fn tenk_royalities(mut self) -> Self {
-        let tenk = tenk_account();
-        if self.payout.len() == 0 || self.payout.contains_key(&tenk) {
+    pub fn apply_platform_fee(mut self, fee: &PlatformFee) -> Self {
+        if fee.bps == 0 || self.payout.is_empty() || self.payout.contains_key(&fee.account_id) {
             return self;
         }
-        // Currently 4.8%, can lower it or make this zero.
-        let bp = 480;
         let mut sum = 0;
         self.payout = self
             .payout
             .into_iter()
             .map(|(account, amount)| {
-                let new_amount = apply_percent(10_000 - bp, amount.0);
+                let new_amount = apply_percent(ONE_HUNDRED_PERCENT_IN_BPS - fee.bps, amount.0);
                 sum += amount.0 - new_amount;
                 (account, new_amount.into())
             })
             .collect();
-        self.payout.insert(tenk_account(), sum.into());
+        self.payout.insert(fee.account_id.clone(), sum.into());
         self
     }
 }
@@ -186,3 +382,27 @@ fn tenk_account() -> AccountId {
     .parse()
     .unwrap()
 }
+
+#[near_bindgen]
+impl Contract {
+    /// Current platform fee taken from royalties
+    pub fn platform_fee(&self) -> PlatformFee {
+        self.platform_fee.clone()
+    }
+
+    /// Update the platform fee. Can only be called by the current fee account.
+    pub fn update_platform_fee(&mut self, platform_fee: PlatformFee) -> bool {
+        require!(
+            env::predecessor_account_id() == self.platform_fee.account_id,
+            "Method is private to platform fee account"
+        );
+        platform_fee.validate();
+        log!(
+            "Platform fee updated to {} bps paid to {}",
+            platform_fee.bps,
+            platform_fee.account_id
+        );
+        self.platform_fee = platform_fee;
+        true
+    }
+}
@@ -0,0 +1,54 @@
+use crate::*;
+
+const GAS_FOR_ON_WITHDRAW: Gas = Gas(parse_gas!("5 Tgas") as u64);
+
+#[near_bindgen]
+impl Contract {
+    /// Proceeds from minting which an account can withdraw
+    pub fn pending_proceeds(&self, account_id: AccountId) -> U128 {
+        self.proceeds.get(&account_id).unwrap_or(0).into()
+    }
+
+    /// Transfer all of the caller's pending proceeds to them.
+    /// If the transfer fails the proceeds are credited back.
+    pub fn withdraw(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let amount = self.proceeds.remove(&account_id).unwrap_or(0);
+        require!(amount > 0, "No proceeds to withdraw");
+        Promise::new(account_id.clone())
+            .transfer(amount)
+            .then(ext_self::on_withdraw(
+                account_id,
+                amount.into(),
+                env::current_account_id(),
+                0,
+                GAS_FOR_ON_WITHDRAW,
+            ))
+    }
+
+    #[private]
+    pub fn on_withdraw(&mut self, account_id: AccountId, amount: U128) {
+        if !is_promise_success(None) {
+            log!("Failed to withdraw {} to {}", amount.0, account_id);
+            self.credit_proceeds(&account_id, amount.0);
+        }
+    }
+}
+
+impl Contract {
+    /// Add each account's share of a payout to the proceeds they can withdraw
+    pub(crate) fn credit_payout(&mut self, payout: Payout) {
+        payout
+            .payout
+            .into_iter()
+            .for_each(|(account_id, amount)| self.credit_proceeds(&account_id, amount.0));
+    }
+
+    pub(crate) fn credit_proceeds(&mut self, account_id: &AccountId, amount: Balance) {
+        if amount == 0 {
+            return;
+        }
+        let balance = self.proceeds.get(account_id).unwrap_or(0) + amount;
+        self.proceeds.insert(account_id, &balance);
+    }
+}
@@ -91,6 +91,13 @@ impl Raffle {
         }
     }
 
+    /// Put a drawn value back, so it can be drawn again
+    pub fn push(&mut self, value: u64) {
+        let lookup_key = self.index_to_lookup_key(self.len);
+        env::storage_write(&lookup_key, &value.to_le_bytes());
+        self.len += 1;
+    }
+
     pub fn draw(&mut self) -> u64 {
         let seed_num = crate::util::get_random_number(0) as u64;
         u64::try_from_slice(&self.swap_remove_raw(seed_num % self.len())).unwrap()
@@ -121,4 +128,21 @@ mod tests {
             testing_env!(context.random_seed(rng.gen()).build());
         }
     }
+
+    #[test]
+    pub fn test_push() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(3);
+        let mut vec = Raffle::new(b"v".to_vec(), 10);
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.build());
+        let drawn = vec.draw();
+        vec.push(drawn);
+        assert_eq!(vec.len(), 10);
+        let mut set: HashSet<u64> = HashSet::new();
+        for _ in 0..10 {
+            assert!(set.insert(vec.draw()));
+            testing_env!(context.random_seed(rng.gen()).build());
+        }
+        assert_eq!(set, (0..10).collect());
+    }
 }
@@ -0,0 +1,104 @@
+use crate::*;
+use events::log_privileged_call;
+use near_sdk::serde_json::json;
+
+/// Commission paid to referrers from the price of the tokens they refer
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct ReferralProgram {
+    /// Part of the mint price credited to the referrer
+    pub bps: BasisPoint,
+    /// Most a single referrer can earn. None, means unlimited
+    pub max_commission: Option<U128>,
+}
+
+/// What an account has earned by referring minters
+#[derive(BorshSerialize, BorshDeserialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct ReferralStats {
+    /// Number of tokens minted with the account as referrer
+    pub tokens: u32,
+    /// Total commission credited to the account's proceeds
+    pub earned: U128,
+}
+
+impl Default for ReferralStats {
+    fn default() -> Self {
+        Self {
+            tokens: 0,
+            earned: U128(0),
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Current referral program, if there is one
+    pub fn referral_program(&self) -> Option<ReferralProgram> {
+        self.referral_program.clone()
+    }
+
+    /// Tokens referred and commission earned by an account
+    pub fn referral_stats(&self, account_id: AccountId) -> ReferralStats {
+        self.referrals.get(&account_id).unwrap_or_default()
+    }
+
+    /// Set the commission paid to referrers. Passing no `referral_program` stops paying commissions.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_referral_program(&mut self, referral_program: Option<ReferralProgram>) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_referral_program",
+            json!({ "referral_program": referral_program }),
+        );
+        if let Some(program) = referral_program.as_ref() {
+            program.validate();
+        }
+        self.referral_program = referral_program;
+        true
+    }
+}
+
+impl ReferralProgram {
+    fn validate(&self) {
+        require!(
+            self.bps <= ONE_HUNDRED_PERCENT_IN_BPS,
+            "referral commission is in basis points and must be between 0 - 10,000"
+        );
+    }
+}
+
+impl Contract {
+    /// Credit `referrer` its commission from the price of `num` tokens minted by `minter`.
+    /// Returns the commission, which is taken out of the proceeds.
+    /// No commission is paid while proceeds are held for a funding goal, since they may be refunded.
+    pub(crate) fn pay_referrer(
+        &mut self,
+        referrer: &AccountId,
+        minter: &AccountId,
+        num: u16,
+        price: Balance,
+    ) -> Balance {
+        require!(referrer != minter, "Cannot refer yourself");
+        let program = match self.referral_program.as_ref() {
+            Some(program) => program,
+            None => return 0,
+        };
+        let mut stats = self.referral_stats(referrer.clone());
+        let commission = if matches!(self.funding_status(), FundingStatus::Raising) {
+            0
+        } else {
+            let commission = apply_percent(program.bps, price);
+            program.max_commission.map_or(commission, |max| {
+                u128::min(commission, max.0.saturating_sub(stats.earned.0))
+            })
+        };
+        stats.tokens += num as u32;
+        stats.earned.0 += commission;
+        self.referrals.insert(referrer, &stats);
+        self.credit_proceeds(referrer, commission);
+        commission
+    }
+}
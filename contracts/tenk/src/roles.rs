@@ -0,0 +1,98 @@
+use crate::*;
+use events::{log_role_granted, log_role_revoked};
+
+/// Permission to call a group of privileged methods.
+/// The owner and admins have every role.
+#[derive(
+    BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub enum Role {
+    /// Change prices, allowances, discounts, start times and other parts of a sale
+    SaleManager,
+    /// Add, remove and update whitelist accounts
+    WhitelistManager,
+    /// Change base URIs and who can update token metadata
+    MetadataManager,
+    /// Create and delete linkdrops and issue passes
+    LinkdropIssuer,
+    /// Change royalties and initial royalties
+    RoyaltyManager,
+    /// Pause and unpause minting, transfers, linkdrops and approvals in an emergency
+    Pauser,
+}
+
+impl Role {
+    const ALL: [Role; 6] = [
+        Role::SaleManager,
+        Role::WhitelistManager,
+        Role::MetadataManager,
+        Role::LinkdropIssuer,
+        Role::RoyaltyManager,
+        Role::Pauser,
+    ];
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Roles an account has. The owner and admins have every role
+    pub fn roles_of(&self, account_id: AccountId) -> Vec<Role> {
+        if self.is_owner(&account_id) || self.is_admin(&account_id) {
+            Role::ALL.to_vec()
+        } else {
+            self.roles.get(&account_id).unwrap_or_default()
+        }
+    }
+
+    /// Grant a role to an account
+    /// @allow ["::owner"]
+    pub fn grant_role(&mut self, account_id: AccountId, role: Role) -> bool {
+        self.assert_owner();
+        let mut roles = self.roles.get(&account_id).unwrap_or_default();
+        if roles.contains(&role) {
+            return false;
+        }
+        roles.push(role);
+        self.roles.insert(&account_id, &roles);
+        log_role_granted(&account_id, role);
+        true
+    }
+
+    /// Revoke a role from an account
+    /// @allow ["::owner"]
+    pub fn revoke_role(&mut self, account_id: AccountId, role: Role) -> bool {
+        self.assert_owner();
+        let mut roles = self.roles.get(&account_id).unwrap_or_default();
+        if !roles.contains(&role) {
+            return false;
+        }
+        roles.retain(|r| *r != role);
+        if roles.is_empty() {
+            self.roles.remove(&account_id);
+        } else {
+            self.roles.insert(&account_id, &roles);
+        }
+        log_role_revoked(&account_id, role);
+        true
+    }
+}
+
+impl Contract {
+    pub(crate) fn has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        self.is_owner(account_id)
+            || self.is_admin(account_id)
+            || self
+                .roles
+                .get(account_id)
+                .map_or(false, |roles| roles.contains(&role))
+    }
+
+    /// Panics unless the caller is the owner, an admin or has been granted `role`
+    pub(crate) fn assert_role(&self, role: Role) {
+        require!(
+            self.has_role(&env::predecessor_account_id(), role),
+            format!("Method requires the {:?} role", role)
+        );
+    }
+}
@@ -0,0 +1,309 @@
+use crate::*;
+use events::log_privileged_call;
+use near_sdk::serde_json::json;
+
+/// Identifier of a series. Its tokens have ids of the form `"{series_id}:{id}"`
+#[witgen]
+pub type SeriesId = String;
+
+/// A collection minted from the same contract, with its own size, sale, royalties and base URI
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Series {
+    pub(crate) title: String,
+    pub(crate) base_uri: String,
+    pub(crate) size: u32,
+    /// Vector of available NFTs
+    pub(crate) raffle: Raffle,
+    pub(crate) sale: Sale,
+    /// Number of tokens each account has minted from this series
+    pub(crate) minted: LookupMap<AccountId, u16>,
+}
+
+/// Arguments to create a new series
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct InitialSeries {
+    /// Used in the title of each token, e.g. "{title} #{id}"
+    pub title: String,
+    /// Base URI of each token's media and reference files, e.g. "{uri}/{id}.json"
+    pub uri: String,
+    pub size: u32,
+    pub sale: Option<Sale>,
+}
+
+/// Information about a series
+#[witgen]
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeriesInfo {
+    pub title: String,
+    pub uri: String,
+    /// Tokens left to be minted
+    pub tokens_left: u32,
+    /// Information about the series' sale.
+    /// `allowance` of the sale applies to each account in both presale and public sale.
+    pub sale_info: SaleInfo,
+}
+
+impl Series {
+    fn tokens_left(&self) -> u32 {
+        self.raffle.len() as u32
+    }
+
+    fn status(&self) -> Status {
+        self.sale.status(self.tokens_left())
+    }
+
+    fn cost(&self, num: u16) -> Balance {
+        self.sale.cost(num, &self.status())
+    }
+
+    fn left_for(&self, account_id: &AccountId) -> Option<u16> {
+        let max = match (self.sale.allowance, self.sale.max_per_account) {
+            (Some(allowance), Some(max)) => Some(u16::min(allowance, max)),
+            (allowance, max) => allowance.or(max),
+        };
+        max.map(|max| max.saturating_sub(self.minted.get(account_id).unwrap_or(0)))
+    }
+
+    fn create_metadata(&self, id: u64, media_extension: &str) -> TokenMetadata {
+        TokenMetadata {
+            title: Some(format!("{} #{}", self.title, id)),
+            media: Some(format!("{}/{}.{}", self.base_uri, id, media_extension)),
+            issued_at: Some(current_time_ms().to_string()),
+            reference: Some(format!("{}/{}.json", self.base_uri, id)),
+            description: None,
+            media_hash: None,
+            copies: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: None,
+            reference_hash: None,
+        }
+    }
+}
+
+pub(crate) fn series_token_id(series_id: &str, id: u64) -> TokenId {
+    format!("{}:{}", series_id, id)
+}
+
+/// Series a token belongs to, `None` if it is part of the contract's main collection
+pub(crate) fn series_of(token_id: &str) -> Option<&str> {
+    token_id.split_once(':').map(|(series_id, _)| series_id)
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Create a new series which is minted with `nft_mint_series`.
+    /// @allow ["::admins", "::owner"]
+    pub fn create_series(&mut self, series_id: SeriesId, series: InitialSeries) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "create_series",
+            json!({ "series_id": series_id, "series": series }),
+        );
+        require!(
+            !series_id.is_empty() && !series_id.contains(':'),
+            "series id must not be empty or contain ':'"
+        );
+        require!(
+            self.series.get(&series_id).is_none(),
+            "series already exists"
+        );
+        let InitialSeries {
+            title,
+            uri,
+            size,
+            sale,
+        } = series;
+        let sale = sale.unwrap_or_default();
+        sale.validate();
+        let series = Series {
+            title,
+            base_uri: uri,
+            size,
+            raffle: Raffle::new(
+                StorageKey::SeriesRaffle {
+                    series_id: series_id.clone(),
+                },
+                size as u64,
+            ),
+            sale,
+            minted: LookupMap::new(StorageKey::SeriesMinted {
+                series_id: series_id.clone(),
+            }),
+        };
+        self.series.insert(&series_id, &series);
+        true
+    }
+
+    /// Replace the sale of a series
+    /// @allow ["::admins", "::owner"]
+    pub fn update_series_sale(&mut self, series_id: SeriesId, sale: Sale) -> bool {
+        self.assert_role(Role::SaleManager);
+        log_privileged_call(
+            "update_series_sale",
+            json!({ "series_id": series_id, "sale": sale }),
+        );
+        sale.validate();
+        // Panics if the series doesn't exist
+        self.get_series(&series_id);
+        self.apply_or_queue(Change::SeriesSale { series_id, sale });
+        true
+    }
+
+    /// Update the base URI of a series. Only affects tokens minted after the change.
+    /// @allow ["::admins", "::owner"]
+    pub fn update_series_uri(&mut self, series_id: SeriesId, uri: String) -> bool {
+        self.assert_role(Role::MetadataManager);
+        log_privileged_call(
+            "update_series_uri",
+            json!({ "series_id": series_id, "uri": uri }),
+        );
+        let mut series = self.get_series(&series_id);
+        log!("New URI for {}: {}", &series_id, &uri);
+        series.base_uri = uri;
+        self.series.insert(&series_id, &series);
+        true
+    }
+
+    /// Mint `num` tokens from a series.
+    /// During the presale only whitelisted accounts can mint.
+    #[payable]
+    pub fn nft_mint_series(&mut self, series_id: SeriesId, num: u16) -> Vec<Token> {
+        self.assert_not_paused(PauseScope::Mint);
+        self.assert_funding_not_failed();
+        let mut series = self.get_series(&series_id);
+        series.sale.assert_within_rate_limit(num);
+        let owner_id = &env::predecessor_account_id();
+        let is_owner = self.is_owner(owner_id);
+        let status = series.status();
+        let mut num = num;
+        if !is_owner {
+            match status {
+                Status::SoldOut => env::panic_str("No NFTs left to mint"),
+                Status::Closed => env::panic_str("Contract currently closed"),
+                Status::Presale => require!(self.whitelisted(owner_id), "Account not on whitelist"),
+                Status::Open => (),
+            };
+            num = series
+                .left_for(owner_id)
+                .map_or(num, |left| u16::min(left, num));
+            require!(num > 0, "Account has no more allowance left");
+        }
+        // Only mint what is left
+        num = u32::min(series.tokens_left(), num as u32) as u16;
+        require!(num > 0, "No NFTs left to mint");
+        let price = if is_owner { 0 } else { series.cost(num) };
+        require!(
+            env::attached_deposit() >= price,
+            "Not enough attached deposit to buy"
+        );
+
+        let initial_storage_usage = env::storage_usage();
+        let media_extension = self
+            .media_extension
+            .clone()
+            .unwrap_or_else(|| "png".to_string());
+        let tokens: Vec<Token> = (0..num)
+            .map(|_| {
+                let id = series.raffle.draw();
+                let token_metadata = Some(series.create_metadata(id, &media_extension));
+                self.tokens.internal_mint_with_refund(
+                    series_token_id(&series_id, id),
+                    owner_id.clone(),
+                    token_metadata,
+                    None,
+                )
+            })
+            .collect();
+        if !is_owner {
+            let minted = series.minted.get(owner_id).unwrap_or(0) + num;
+            series.minted.insert(owner_id, &minted);
+        }
+        self.series.insert(&series_id, &series);
+        self.record_sale(owner_id, num, price, &status);
+        if price > 0 {
+            self.receive_proceeds(&tokens, price, Some(&series_id));
+        }
+
+        let storage_used = env::storage_usage() - initial_storage_usage;
+        self.pay_for_mint(owner_id, price, storage_used);
+        log_mint(owner_id, &tokens);
+        tokens
+    }
+
+    /// Ids of all series
+    pub fn series_ids(&self) -> Vec<SeriesId> {
+        self.series.keys_as_vector().to_vec()
+    }
+
+    /// Information about a series and its sale
+    pub fn get_series_info(&self, series_id: SeriesId) -> SeriesInfo {
+        let series = self.get_series(&series_id);
+        let status = series.status();
+        SeriesInfo {
+            sale_info: SaleInfo {
+                presale_start: series.sale.presale_start.unwrap_or(MAX_DATE),
+                sale_start: series.sale.public_sale_start.unwrap_or(MAX_DATE),
+                price: series.sale.price(&status).into(),
+                status,
+                token_final_supply: series.size as u64,
+                platform_fee: self.platform_fee.clone(),
+            },
+            tokens_left: series.tokens_left(),
+            title: series.title,
+            uri: series.base_uri,
+        }
+    }
+
+    /// Cost of minting `num` tokens from a series, not including storage
+    pub fn series_total_cost(&self, series_id: SeriesId, num: u16, minter: &AccountId) -> U128 {
+        let series = self.get_series(&series_id);
+        series.sale.assert_within_rate_limit(num);
+        if self.is_owner(minter) {
+            return 0.into();
+        }
+        series.cost(num).into()
+    }
+
+    /// Deposit to attach to mint `num` tokens from a series: their `series_total_cost` plus the
+    /// cost of storing them. What isn't used is refunded.
+    pub fn series_mint_deposit(&self, series_id: SeriesId, num: u16, minter: &AccountId) -> U128 {
+        let price = self.series_total_cost(series_id, num, minter).0;
+        (price + num as Balance * self.token_storage_cost().0).into()
+    }
+
+    /// How many tokens an account is still allowed to mint from a series. None, means unlimited
+    pub fn series_remaining_allowance(
+        &self,
+        series_id: SeriesId,
+        account_id: &AccountId,
+    ) -> Option<u16> {
+        self.get_series(&series_id).left_for(account_id)
+    }
+}
+
+impl Contract {
+    /// Replace the sale of an existing series
+    pub(crate) fn set_series_sale(&mut self, series_id: &str, sale: Sale) {
+        let mut series = self.get_series(series_id);
+        series.sale = sale;
+        self.series.insert(&series_id.to_string(), &series);
+    }
+
+    pub(crate) fn get_series(&self, series_id: &str) -> Series {
+        self.series
+            .get(&series_id.to_string())
+            .unwrap_or_else(|| env::panic_str("No such series"))
+    }
+
+    /// Royalties of the series a token belongs to, if it has any
+    pub(crate) fn series_royalties(&self, token_id: &str) -> Option<Royalties> {
+        series_of(token_id)
+            .and_then(|series_id| self.series.get(&series_id.to_string()))
+            .and_then(|series| series.sale.royalties)
+    }
+}
@@ -37,6 +37,7 @@ impl NonFungibleTokenCore for Contract {
         approval_id: Option<u64>,
         memo: Option<String>,
     ) {
+        self.assert_not_paused(PauseScope::Transfer);
         self.tokens
             .nft_transfer(receiver_id, token_id, approval_id, memo)
     }
@@ -83,6 +84,7 @@ impl NonFungibleTokenCore for Contract {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<bool> {
+        self.assert_not_paused(PauseScope::Transfer);
         self.tokens
             .nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
     }
@@ -143,6 +145,7 @@ impl NonFungibleTokenApproval for Contract {
         account_id: AccountId,
         msg: Option<String>,
     ) -> Option<Promise> {
+        self.assert_not_paused(PauseScope::Approval);
         self.tokens.nft_approve(token_id, account_id, msg)
     }
 
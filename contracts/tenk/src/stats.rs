@@ -0,0 +1,93 @@
+use crate::*;
+
+/// Totals of all sales made by the contract
+#[derive(BorshSerialize, BorshDeserialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct SaleStats {
+    /// Total paid for minted tokens, not including storage
+    pub total_proceeds: U128,
+    /// Paid for tokens minted before the public sale
+    pub presale_proceeds: U128,
+    /// Paid for tokens minted during the public sale
+    pub public_sale_proceeds: U128,
+    /// Part of the proceeds split to accounts other than the owner by `initial_royalties`,
+    /// including the platform fee
+    pub royalties_paid: U128,
+    pub tokens_minted: u64,
+}
+
+impl Default for SaleStats {
+    fn default() -> Self {
+        Self {
+            total_proceeds: U128(0),
+            presale_proceeds: U128(0),
+            public_sale_proceeds: U128(0),
+            royalties_paid: U128(0),
+            tokens_minted: 0,
+        }
+    }
+}
+
+/// Totals of what an account has minted
+#[derive(BorshSerialize, BorshDeserialize, Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct AccountStats {
+    /// Total paid for minted tokens, not including storage
+    pub spent: U128,
+    pub minted: u32,
+}
+
+impl Default for AccountStats {
+    fn default() -> Self {
+        Self {
+            spent: U128(0),
+            minted: 0,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Totals of proceeds raised, royalties paid and tokens minted
+    pub fn sale_stats(&self) -> SaleStats {
+        self.sale_stats.get().unwrap_or_default()
+    }
+
+    /// Total spent and tokens minted by an account
+    pub fn account_stats(&self, account_id: AccountId) -> AccountStats {
+        self.account_stats.get(&account_id).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    /// Record that `account_id` minted `num` tokens for `amount` while the sale was in `status`
+    pub(crate) fn record_sale(
+        &mut self,
+        account_id: &AccountId,
+        num: u16,
+        amount: Balance,
+        status: &Status,
+    ) {
+        let mut stats = self.sale_stats();
+        stats.total_proceeds.0 += amount;
+        match status {
+            Status::Presale | Status::Closed => stats.presale_proceeds.0 += amount,
+            Status::Open | Status::SoldOut => stats.public_sale_proceeds.0 += amount,
+        }
+        stats.tokens_minted += num as u64;
+        self.sale_stats.set(&stats);
+
+        let mut account_stats = self.account_stats(account_id.clone());
+        account_stats.spent.0 += amount;
+        account_stats.minted += num as u32;
+        self.account_stats.insert(account_id, &account_stats);
+    }
+
+    pub(crate) fn record_royalties_paid(&mut self, amount: Balance) {
+        let mut stats = self.sale_stats();
+        stats.royalties_paid.0 += amount;
+        self.sale_stats.set(&stats);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::*;
+use events::log_privileged_call;
+use near_sdk::serde_json::json;
+
+/// Configuration change which only takes effect once the timelock has passed
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub enum Change {
+    /// New value of `royalties`, see `update_royalties`
+    Royalties(Royalties),
+    /// New value of `initial_royalties`, see `update_initial_royalties`
+    InitialRoyalties(Royalties),
+    /// New public sale price, see `update_price`
+    Price(U128),
+    /// New base URI, see `update_uri`
+    Uri(String),
+    /// Shorter timelock or no timelock, see `update_timelock`
+    Timelock(Option<u64>),
+    /// New presale price, see `update_presale_price`
+    PresalePrice(Option<U128>),
+    /// New quantity discounts, see `update_discounts`
+    Discounts(Option<Vec<QuantityDiscount>>),
+    /// New bundles, see `update_bundles`
+    Bundles(Option<Vec<Bundle>>),
+    /// Royalties of specific tokens, see `set_token_royalties`
+    TokenRoyalties {
+        token_ids: Vec<TokenId>,
+        royalties: Option<Royalties>,
+    },
+    /// New royalty range, see `add_royalty_range`
+    AddRoyaltyRange(RoyaltyRange),
+    /// Start of the royalty range to remove, see `remove_royalty_range`
+    RemoveRoyaltyRange(u32),
+    /// New sale of a series, see `update_series_sale`
+    SeriesSale { series_id: SeriesId, sale: Sale },
+    /// Hash of the staged code to deploy, see `upgrade`
+    Upgrade(String),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct PendingChange {
+    pub id: u32,
+    pub change: Change,
+    /// Time in ms after which the change can be executed with `execute_change`
+    pub execute_after: TimestampMs,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Delay in ms before changes to royalties, prices and base URI take effect. None, means they
+    /// take effect immediately
+    pub fn timelock(&self) -> Option<u64> {
+        self.timelock
+    }
+
+    /// Changes waiting for their timelock to pass
+    pub fn pending_changes(&self) -> Vec<PendingChange> {
+        self.pending_changes.clone()
+    }
+
+    /// Update the timelock. A longer timelock takes effect immediately, while a shorter one,
+    /// or removing it, is itself queued behind the current timelock.
+    /// @allow ["::owner"]
+    pub fn update_timelock(&mut self, timelock: Option<u64>) -> bool {
+        self.assert_owner();
+        log_privileged_call("update_timelock", json!({ "timelock": timelock }));
+        self.apply_or_queue(Change::Timelock(timelock));
+        true
+    }
+
+    /// Execute a pending change once its timelock has passed
+    /// @allow ["::admins", "::owner"]
+    pub fn execute_change(&mut self, id: u32) -> bool {
+        let index = self.pending_change_index(id);
+        self.assert_can_change(&self.pending_changes[index].change);
+        log_privileged_call("execute_change", json!({ "id": id }));
+        require!(
+            self.pending_changes[index].execute_after <= current_time_ms(),
+            "Change is still timelocked"
+        );
+        let pending_change = self.pending_changes.remove(index);
+        self.apply_change(pending_change.change);
+        true
+    }
+
+    /// Cancel a pending change
+    /// @allow ["::admins", "::owner"]
+    pub fn cancel_change(&mut self, id: u32) -> bool {
+        let index = self.pending_change_index(id);
+        self.assert_can_change(&self.pending_changes[index].change);
+        log_privileged_call("cancel_change", json!({ "id": id }));
+        self.pending_changes.remove(index);
+        true
+    }
+}
+
+impl Contract {
+    /// Apply a change now if there is no timelock, or if it makes the timelock longer.
+    /// Otherwise queue it until the timelock has passed.
+    pub(crate) fn apply_or_queue(&mut self, change: Change) {
+        let delay = match (self.timelock, &change) {
+            (None, _) => 0,
+            (Some(current), Change::Timelock(Some(new))) if *new >= current => 0,
+            (Some(current), _) => current,
+        };
+        if delay == 0 {
+            self.apply_change(change);
+            return;
+        }
+        let execute_after = current_time_ms() + delay;
+        let id = self.next_change_id;
+        self.next_change_id += 1;
+        log!("Change {} can be executed after {}", id, execute_after);
+        self.pending_changes.push(PendingChange {
+            id,
+            change,
+            execute_after,
+        });
+    }
+
+    fn apply_change(&mut self, change: Change) {
+        match change {
+            Change::Royalties(royalties) => self.sale.royalties = Some(royalties),
+            Change::InitialRoyalties(initial_royalties) => {
+                self.sale.initial_royalties = Some(initial_royalties)
+            }
+            Change::Price(price) => self.sale.price = price,
+            Change::Uri(uri) => {
+                let mut metadata = self.metadata.get().unwrap();
+                log!("New URI: {}", &uri);
+                metadata.base_uri = Some(uri);
+                self.metadata.set(&metadata);
+            }
+            Change::Timelock(timelock) => self.timelock = timelock,
+            Change::PresalePrice(presale_price) => self.sale.presale_price = presale_price,
+            Change::Discounts(discounts) => {
+                self.sale.discounts = discounts;
+                self.sale.validate();
+            }
+            Change::Bundles(bundles) => {
+                self.sale.bundles = bundles;
+                self.sale.validate();
+            }
+            Change::TokenRoyalties {
+                token_ids,
+                royalties,
+            } => self.set_royalties_of(&token_ids, royalties.as_ref()),
+            Change::AddRoyaltyRange(range) => self.insert_royalty_range(range),
+            Change::RemoveRoyaltyRange(start) => {
+                let index = self.royalty_range_index(start);
+                self.royalty_ranges.swap_remove(index);
+            }
+            Change::SeriesSale { series_id, sale } => self.set_series_sale(&series_id, sale),
+            Change::Upgrade(code_hash) => self.deploy_staged(code_hash),
+        }
+    }
+
+    /// Panics unless the caller could make `change` directly
+    fn assert_can_change(&self, change: &Change) {
+        match change {
+            Change::Royalties(_)
+            | Change::InitialRoyalties(_)
+            | Change::TokenRoyalties { .. }
+            | Change::AddRoyaltyRange(_)
+            | Change::RemoveRoyaltyRange(_) => self.assert_role(Role::RoyaltyManager),
+            Change::Price(_)
+            | Change::PresalePrice(_)
+            | Change::Discounts(_)
+            | Change::Bundles(_)
+            | Change::SeriesSale { .. } => self.assert_role(Role::SaleManager),
+            Change::Uri(_) => self.assert_role(Role::MetadataManager),
+            Change::Timelock(_) | Change::Upgrade(_) => self.assert_owner(),
+        }
+    }
+
+    fn pending_change_index(&self, id: u32) -> usize {
+        self.pending_changes
+            .iter()
+            .position(|pending_change| pending_change.id == id)
+            .unwrap_or_else(|| env::panic_str("No such pending change"))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{test_utils::VMContextBuilder, testing_env};
+    use std::collections::HashMap;
+
+    fn owner() -> AccountId {
+        AccountId::new_unchecked("owner.near".to_string())
+    }
+
+    fn royalties() -> Royalties {
+        Royalties {
+            accounts: HashMap::from([(owner(), 10_000)]),
+            percent: 1_000,
+        }
+    }
+
+    /// Contract owned by `owner()` with a timelock of one second and the following calls made by
+    /// the owner at `time_ms`
+    fn timelocked_at(time_ms: u64) -> Contract {
+        called_by_owner_at(0);
+        let mut contract = Contract::new_default_meta(
+            owner(),
+            InitialMetadata {
+                name: "name".to_string(),
+                symbol: "sym".to_string(),
+                uri: "https://".to_string(),
+                ..Default::default()
+            },
+            100,
+            None,
+            None,
+            None,
+            None,
+        );
+        contract.update_timelock(Some(1_000));
+        called_by_owner_at(time_ms);
+        contract
+    }
+
+    fn called_by_owner_at(time_ms: u64) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(owner())
+            .block_timestamp(time_ms * 1_000_000)
+            .build());
+    }
+
+    #[test]
+    fn timelocked_change_waits_for_delay() {
+        let mut contract = timelocked_at(0);
+        contract.update_price(U128(5));
+        assert_eq!(contract.sale.price.0, 0);
+        assert_eq!(contract.pending_changes()[0].execute_after, 1_000);
+        called_by_owner_at(1_000);
+        contract.execute_change(0);
+        assert_eq!(contract.sale.price.0, 5);
+        assert!(contract.pending_changes().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Change is still timelocked")]
+    fn timelocked_change_executed_early() {
+        let mut contract = timelocked_at(0);
+        contract.update_uri("https://new".to_string());
+        called_by_owner_at(999);
+        contract.execute_change(0);
+    }
+
+    #[test]
+    fn cancelled_change_is_never_applied() {
+        let mut contract = timelocked_at(0);
+        contract.update_royalties(royalties());
+        contract.cancel_change(0);
+        assert!(contract.pending_changes().is_empty());
+        assert!(contract.sale.royalties.is_none());
+    }
+
+    #[test]
+    fn timelock_covers_every_price_and_royalty_change() {
+        let mut contract = timelocked_at(0);
+        contract.start_sale(Some(U128(5)));
+        contract.update_presale_price(Some(U128(5)));
+        contract.update_bundles(Some(vec![Bundle {
+            num: 2,
+            price: U128(1),
+        }]));
+        contract.add_royalty_range(0, 10, royalties());
+        contract.update_initial_royalties(royalties());
+        assert_eq!(contract.sale.price.0, 0);
+        assert!(contract.sale.presale_price.is_none());
+        assert!(contract.sale.bundles.is_none());
+        assert!(contract.royalty_ranges().is_empty());
+        assert!(contract.sale.initial_royalties.is_none());
+        assert_eq!(contract.pending_changes().len(), 5);
+        called_by_owner_at(1_000);
+        (0..5).for_each(|id| {
+            contract.execute_change(id);
+        });
+        assert_eq!(contract.sale.price.0, 5);
+        assert_eq!(contract.royalty_ranges().len(), 1);
+        assert!(contract.sale.initial_royalties.is_some());
+    }
+
+    #[test]
+    fn only_longer_timelock_is_immediate() {
+        let mut contract = timelocked_at(0);
+        contract.update_timelock(Some(2_000));
+        assert_eq!(contract.timelock(), Some(2_000));
+        contract.update_timelock(None);
+        assert_eq!(contract.timelock(), Some(2_000));
+        called_by_owner_at(2_000);
+        contract.execute_change(0);
+        assert_eq!(contract.timelock(), None);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::*;
+use events::log_metadata_update;
+
+/// Fields of a token's metadata which can be changed after it is minted.
+/// Fields left out are not changed.
+#[derive(Deserialize, Serialize, Default)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct TokenMetadataUpdate {
+    /// Anything extra the NFT wants to store on-chain, e.g. stringified JSON of a token's level
+    pub extra: Option<String>,
+    /// Free-form description
+    pub description: Option<String>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// Update the mutable fields of a token's metadata and set `updated_at` to the current time.
+    /// Can only be called by the token's owner or an approved metadata updater, e.g. a game contract.
+    /// Any extra storage used must be covered by the attached deposit, the rest is refunded.
+    #[payable]
+    pub fn nft_update_metadata(
+        &mut self,
+        token_id: TokenId,
+        update: TokenMetadataUpdate,
+    ) -> TokenMetadata {
+        let caller = env::predecessor_account_id();
+        let owner_id = self
+            .tokens
+            .owner_by_id
+            .get(&token_id)
+            .expect("No such token_id");
+        require!(
+            caller == owner_id || self.metadata_updaters.contains(&caller),
+            "Only the token owner or a metadata updater can update metadata"
+        );
+        let initial_storage_usage = env::storage_usage();
+        let token_metadata_by_id = self.tokens.token_metadata_by_id.as_mut().unwrap();
+        let mut metadata = token_metadata_by_id
+            .get(&token_id)
+            .expect("No metadata for token_id");
+        let TokenMetadataUpdate { extra, description } = update;
+        if extra.is_some() {
+            metadata.extra = extra;
+        }
+        if description.is_some() {
+            metadata.description = description;
+        }
+        metadata.updated_at = Some(current_time_ms().to_string());
+        token_metadata_by_id.insert(&token_id, &metadata);
+
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        refund_deposit_to_account(storage_used, caller);
+        log_metadata_update(&[token_id]);
+        metadata
+    }
+}
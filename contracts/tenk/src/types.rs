@@ -54,6 +54,14 @@ impl Allowance {
         self.max - self.used
     }
 
+    pub fn max(&self) -> u16 {
+        self.max
+    }
+
+    pub fn used(&self) -> u16 {
+        self.used
+    }
+
     pub fn use_num(&mut self, num: u16) {
         self.used += num
     }
@@ -68,7 +76,7 @@ impl Allowance {
     }
 }
 
-#[derive(Deserialize, Serialize, BorshSerialize, BorshDeserialize)]
+#[derive(Deserialize, Serialize, BorshSerialize, BorshDeserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 #[witgen]
 pub struct Sale {
@@ -76,10 +84,58 @@ pub struct Sale {
     pub initial_royalties: Option<Royalties>,
     pub presale_start: Option<TimestampMs>,
     pub public_sale_start: Option<TimestampMs>,
+    /// Number of tokens an account can mint in the public sale, including those it minted in the presale
     pub allowance: Option<u16>,
+    /// Maximum number of tokens an account can ever mint, across all phases
+    pub max_per_account: Option<u16>,
     pub presale_price: Option<U128>,
     pub price: U128,
     pub mint_rate_limit: Option<u16>,
+    /// Whose allowance is used when minting to another account with `nft_mint_to`.
+    /// Defaults to the account paying.
+    pub gift_allowance: Option<GiftAllowance>,
+    /// Price of a token minted by redeeming a pass. Free when not set
+    pub pass_price: Option<U128>,
+    /// Discounts for minting at least a given number of tokens at once
+    pub discounts: Option<Vec<QuantityDiscount>>,
+    /// Fixed prices for minting an exact number of tokens at once, e.g. 3 for 2N.
+    /// Takes precedence over discounts and applies in both presale and public sale.
+    pub bundles: Option<Vec<Bundle>>,
+}
+
+/// Discount on the price of minting `min` or more tokens at once
+#[derive(Deserialize, Serialize, BorshSerialize, BorshDeserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct QuantityDiscount {
+    pub min: u16,
+    pub bps: BasisPoint,
+}
+
+/// Price of minting exactly `num` tokens at once
+#[derive(Deserialize, Serialize, BorshSerialize, BorshDeserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub struct Bundle {
+    pub num: u16,
+    pub price: U128,
+}
+
+/// Account whose allowance is used when minting to another account
+#[derive(Deserialize, Serialize, BorshSerialize, BorshDeserialize, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+#[witgen]
+pub enum GiftAllowance {
+    /// The account paying for the tokens
+    Payer,
+    /// The account receiving the tokens, if it has approved the payer with `approve_gifts`
+    Receiver,
+}
+
+impl Default for GiftAllowance {
+    fn default() -> Self {
+        Self::Payer
+    }
 }
 
 impl Default for Sale {
@@ -92,8 +148,13 @@ impl Default for Sale {
             presale_start: Default::default(),
             public_sale_start: Default::default(),
             allowance: Default::default(),
+            max_per_account: Default::default(),
             presale_price: Default::default(),
             mint_rate_limit: Some(10),
+            gift_allowance: Default::default(),
+            pass_price: Default::default(),
+            discounts: Default::default(),
+            bundles: Default::default(),
         }
     }
 }
@@ -106,11 +167,77 @@ impl Sale {
         if let Some(r) = self.initial_royalties.as_ref() {
             r.validate()
         }
+        self.discounts.iter().flatten().for_each(|discount| {
+            require!(
+                discount.bps <= ONE_HUNDRED_PERCENT_IN_BPS,
+                "discount is in basis points and must be between 0 - 10,000"
+            );
+            require!(
+                self.within_rate_limit(discount.min),
+                "discount cannot require more tokens than mint_rate_limit"
+            );
+        });
+        self.bundles.iter().flatten().for_each(|bundle| {
+            require!(
+                bundle.num > 0 && self.within_rate_limit(bundle.num),
+                "bundle must have between 1 and mint_rate_limit tokens"
+            );
+        });
+    }
+
+    fn within_rate_limit(&self, num: u16) -> bool {
+        self.mint_rate_limit.map_or(true, |limit| num <= limit)
+    }
+
+    /// Panics if more than `mint_rate_limit` tokens are minted at once
+    pub fn assert_within_rate_limit(&self, num: u16) {
+        require!(self.within_rate_limit(num), "over mint limit");
+    }
+
+    /// Current state of the sale given how many tokens are left to mint
+    pub fn status(&self, tokens_left: u32) -> Status {
+        if tokens_left == 0 {
+            return Status::SoldOut;
+        }
+        let current_time = current_time_ms();
+        match (self.presale_start, self.public_sale_start) {
+            (_, Some(public)) if public < current_time => Status::Open,
+            (Some(pre), _) if pre < current_time => Status::Presale,
+            (_, _) => Status::Closed,
+        }
+    }
+
+    /// Price of one token during a given state of the sale
+    pub fn price(&self, status: &Status) -> u128 {
+        match status {
+            Status::Presale | Status::Closed => self.presale_price.unwrap_or(self.price),
+            Status::Open | Status::SoldOut => self.price,
+        }
+        .into()
+    }
+
+    /// Price of minting `num` tokens at once during a given state of the sale.
+    /// A bundle for exactly `num` tokens sets the price, otherwise the largest discount
+    /// `num` qualifies for is applied.
+    pub fn cost(&self, num: u16, status: &Status) -> u128 {
+        if let Some(bundle) = self.bundles.iter().flatten().find(|b| b.num == num) {
+            return bundle.price.0;
+        }
+        let full_price = num as u128 * self.price(status);
+        self.discounts
+            .iter()
+            .flatten()
+            .filter(|discount| discount.min <= num)
+            .map(|discount| discount.bps)
+            .max()
+            .map_or(full_price, |bps| {
+                full_price - apply_percent(bps, full_price)
+            })
     }
 }
 /// Current state of contract
 #[witgen]
-#[derive(Serialize)]
+#[derive(Serialize, BorshSerialize, BorshDeserialize)]
 #[serde(crate = "near_sdk::serde")]
 pub enum Status {
     /// Not open for any sales
@@ -150,6 +277,8 @@ pub struct SaleInfo {
     pub token_final_supply: u64,
     /// Current price for one token
     pub price: U128,
+    /// Fee taken by the platform from royalties, including initial royalties on mint
+    pub platform_fee: PlatformFee,
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -182,6 +311,8 @@ mod tests {
                 ..Default::default()
             }),
             None,
+            None,
+            None,
         )
     }
 
@@ -190,4 +321,38 @@ mod tests {
         let contract = new_contract();
         assert_eq!(contract.cost_per_token(&account()).0, TEN);
     }
+
+    #[test]
+    fn check_discounts_and_bundles() {
+        let sale = Sale {
+            price: TEN.into(),
+            discounts: Some(vec![
+                QuantityDiscount { min: 3, bps: 500 },
+                QuantityDiscount { min: 5, bps: 1_000 },
+            ]),
+            bundles: Some(vec![Bundle {
+                num: 4,
+                price: (3 * TEN).into(),
+            }]),
+            ..Default::default()
+        };
+        sale.validate();
+        assert_eq!(sale.cost(2, &Status::Open), 2 * TEN);
+        assert_eq!(sale.cost(3, &Status::Open), 3 * TEN / 100 * 95);
+        assert_eq!(sale.cost(4, &Status::Open), 3 * TEN);
+        assert_eq!(sale.cost(5, &Status::Open), 5 * TEN / 100 * 90);
+    }
+
+    #[test]
+    #[should_panic(expected = "discount cannot require more tokens than mint_rate_limit")]
+    fn discount_over_rate_limit() {
+        Sale {
+            discounts: Some(vec![QuantityDiscount {
+                min: 11,
+                bps: 1_000,
+            }]),
+            ..Default::default()
+        }
+        .validate();
+    }
 }
@@ -12,6 +12,26 @@ impl Contract {
         self.admins.to_vec()
     }
 
+    /// Account proposed as the new owner, if there is one
+    pub fn pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+
+    /// Account with the same permissions as the owner, if there is one
+    pub fn backup_owner(&self) -> Option<AccountId> {
+        self.backup_owner.clone()
+    }
+
+    /// Accounts allowed to update the mutable metadata of any token
+    pub fn metadata_updaters(&self) -> Vec<AccountId> {
+        self.metadata_updaters.to_vec()
+    }
+
+    /// Contracts allowed to mint on behalf of users
+    pub fn trusted_minters(&self) -> Vec<AccountId> {
+        self.trusted_minters.to_vec()
+    }
+
     /// Check whether an account is allowed to mint during the presale
     pub fn whitelisted(&self, account_id: &AccountId) -> bool {
         self.whitelist.contains_key(account_id)
@@ -23,8 +43,17 @@ impl Contract {
             .into()
     }
 
+    /// Price of `num` tokens, after any bundle price or discount, not including storage.
+    /// Panics if `num` is over the `mint_rate_limit`.
     pub fn total_cost(&self, num: u16, minter: &AccountId) -> U128 {
-        (num as Balance * self.cost_per_token(minter).0).into()
+        self.sale.assert_within_rate_limit(num);
+        self.price_of(num, minter).into()
+    }
+
+    /// Deposit to attach to mint `num` tokens: their `total_cost` plus the cost of storing them.
+    /// What isn't used is refunded.
+    pub fn mint_deposit(&self, num: u16, minter: &AccountId) -> U128 {
+        (self.total_cost(num, minter).0 + num as Balance * self.token_storage_cost().0).into()
     }
 
     /// Flat cost of one token
@@ -52,18 +81,35 @@ impl Contract {
         self.metadata.get().unwrap()
     }
 
-    /// How many tokens an account is still allowed to mint. None, means unlimited
+    /// How many tokens an account is still allowed to mint. None, means unlimited.
+    /// During the presale this is what is left of its whitelist allowance, otherwise what is left
+    /// of the larger of its whitelist allowance and the public `allowance`. Both are capped by what
+    /// is left of `max_per_account`.
     pub fn remaining_allowance(&self, account_id: &AccountId) -> Option<u16> {
+        let minted = self.minted_by(account_id);
+        let whitelist_allowance = self.whitelist.get(account_id);
         let allowance = if self.is_presale() {
-            0
-        } else if let Some(allowance) = self.sale.allowance {
-            allowance
+            Some(whitelist_allowance.map_or(0, |a| a.left()))
         } else {
-            return None;
+            self.sale.allowance.map(|allowance| {
+                whitelist_allowance
+                    .map_or(allowance, |a| a.raise_max(allowance).max())
+                    .saturating_sub(minted)
+            })
         };
-        self.whitelist
-            .get(account_id)
-            .map(|a| a.raise_max(allowance).left())
+        let lifetime_left = self
+            .sale
+            .max_per_account
+            .map(|max| max.saturating_sub(minted));
+        match (allowance, lifetime_left) {
+            (Some(allowance), Some(left)) => Some(u16::min(allowance, left)),
+            (allowance, left) => allowance.or(left),
+        }
+    }
+
+    /// Number of tokens an account has minted from the main collection
+    pub fn minted_by_account(&self, account_id: &AccountId) -> u16 {
+        self.minted_by(account_id)
     }
 
     /// Max number of mints in one transaction. None, means unlimited
@@ -79,6 +125,7 @@ impl Contract {
             status: self.get_status(),
             price: self.price().into(),
             token_final_supply: self.initial(),
+            platform_fee: self.platform_fee.clone(),
         }
     }
 